@@ -15,9 +15,9 @@ fi
 echo "Deploying to ESP32-S3 with profile: $PROFILE, port: $PORT"
 
 if [[ "$PROFILE" == "release" ]]; then
-    MCU=esp32s3 cargo build --target xtensa-esp32s3-espidf --release
+    MCU=esp32s3 cargo build --target xtensa-esp32s3-espidf --features esp --release
     espflash flash --port "$PORT" --chip esp32s3 target/xtensa-esp32s3-espidf/release/mousefood-benchmark
 else
-    MCU=esp32s3 cargo build --target xtensa-esp32s3-espidf
+    MCU=esp32s3 cargo build --target xtensa-esp32s3-espidf --features esp
     espflash flash --port "$PORT" --chip esp32s3 target/xtensa-esp32s3-espidf/debug/mousefood-benchmark
 fi
@@ -0,0 +1,197 @@
+use std::time::Duration;
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+use crate::input::ButtonEvent;
+use crate::report::Report;
+use crate::scene::{Scene, SceneRegistry, SceneRun, SceneRunner};
+
+/// Upper bound on the stages of a single scene, in case it never exits on a press
+const MAX_STAGES: usize = 32;
+
+/// How long each stage of an unattended run lasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Frames(usize),
+    Time(Duration),
+}
+
+impl Budget {
+    /// Reads the budget from `MOUSEFOOD_AUTORUN_FRAMES` or `MOUSEFOOD_AUTORUN_SECS`
+    /// at build time, defaulting to 10 seconds
+    pub fn from_env() -> Self {
+        let frames = option_env!("MOUSEFOOD_AUTORUN_FRAMES").and_then(|n| n.parse().ok());
+        let secs = option_env!("MOUSEFOOD_AUTORUN_SECS").and_then(|n| n.parse().ok());
+
+        match (frames, secs) {
+            (Some(frames), _) => Budget::Frames(frames),
+            (None, Some(secs)) => Budget::Time(Duration::from_secs(secs)),
+            (None, None) => Budget::Time(Duration::from_secs(10)),
+        }
+    }
+
+    fn is_spent(&self, run: SceneRun) -> bool {
+        match *self {
+            Budget::Frames(frames) => run.frames >= frames,
+            Budget::Time(time) => run.elapsed >= time,
+        }
+    }
+}
+
+/// Reads how many rounds to run from `MOUSEFOOD_AUTORUN_ROUNDS` at build time,
+/// defaulting to one; `0` runs rounds until the device is reset
+pub fn rounds_from_env() -> Option<u32> {
+    match option_env!("MOUSEFOOD_AUTORUN_ROUNDS").and_then(|n| n.parse().ok()).unwrap_or(1) {
+        0 => None,
+        rounds => Some(rounds),
+    }
+}
+
+/// Runs scenes without button input, pressing on the user's behalf once a
+/// stage has used up its budget
+///
+/// A stage lasts until the next simulated press, so every text style mode gets
+/// its own budget. Busy scenes are never interrupted: the microbenchmark scenes
+/// complete all their benchmarks before the first press.
+#[derive(Debug, Clone, Copy)]
+pub struct AutoRun {
+    budget: Budget,
+}
+
+impl AutoRun {
+    pub fn new(budget: Budget) -> Self {
+        Self { budget }
+    }
+
+    /// Runs every scene of the registry in order
+    pub fn run<B: Backend>(
+        &self,
+        registry: &SceneRegistry,
+        runner: &mut SceneRunner,
+        terminal: &mut Terminal<B>,
+    ) -> Result<Report, B::Error> {
+        let mut report = Report::default();
+        for (name, create) in registry.iter() {
+            self.run_scene(name, create().as_mut(), runner, terminal, &mut report)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Runs a single scene through all of its stages, adding them to `report`
+    pub fn run_scene<B: Backend>(
+        &self,
+        name: &'static str,
+        scene: &mut dyn Scene,
+        runner: &mut SceneRunner,
+        terminal: &mut Terminal<B>,
+        report: &mut Report,
+    ) -> Result<(), B::Error> {
+        for stage in 1..=MAX_STAGES {
+            runner.run_until(scene, terminal, |scene, run| {
+                (!scene.is_busy() && self.budget.is_spent(run)).then_some(())
+            })?;
+
+            report.record_stage(name, stage, &*scene, runner);
+            if scene.handle_input(ButtonEvent::Press).is_some() {
+                break;
+            }
+        }
+
+        report.record_microbenchmarks(name, &*scene);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Frame;
+    use ratatui::backend::TestBackend;
+    use crate::benchmark::Benchmark;
+    use crate::fps::FpsWidget;
+    use crate::scene::SceneExit;
+
+    /// Busy for its first `busy` frames
+    struct Stages {
+        busy: usize,
+        frames: usize,
+    }
+
+    impl Scene for Stages {
+        fn update(&mut self, _elapsed: Duration) {
+            self.frames += 1;
+        }
+
+        fn render(&mut self, _frame: &mut Frame, _fps: &FpsWidget) {}
+
+        fn is_busy(&self) -> bool {
+            self.frames < self.busy
+        }
+    }
+
+    struct Stubborn;
+
+    impl Scene for Stubborn {
+        fn handle_input(&mut self, _event: ButtonEvent) -> Option<SceneExit> {
+            None
+        }
+
+        fn render(&mut self, _frame: &mut Frame, _fps: &FpsWidget) {}
+    }
+
+    fn terminal() -> Terminal<TestBackend> {
+        Terminal::new(TestBackend::new(53, 24)).unwrap()
+    }
+
+    #[test]
+    fn test_text_style_modes_are_separate_stages() {
+        let registry = SceneRegistry::new().register("text", Benchmark::new);
+        let report = AutoRun::new(Budget::Frames(3))
+            .run(&registry, &mut SceneRunner::new(), &mut terminal())
+            .unwrap();
+
+        let stages: Vec<_> = report.stages.iter().map(|s| (s.scene, s.stage, s.run.frames)).collect();
+        assert_eq!(stages, (1..=8).map(|stage| ("text", stage, 3)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_busy_scene_outlasts_budget() {
+        let registry = SceneRegistry::new()
+            .register("busy", || Stages { busy: 7, frames: 0 })
+            .register("idle", || Stages { busy: 0, frames: 0 });
+
+        let report = AutoRun::new(Budget::Frames(2))
+            .run(&registry, &mut SceneRunner::new(), &mut terminal())
+            .unwrap();
+
+        let frames: Vec<_> = report.stages.iter().map(|s| s.run.frames).collect();
+        assert_eq!(frames, [7, 2]);
+    }
+
+    #[test]
+    fn test_stages_are_capped() {
+        let mut report = Report::default();
+        AutoRun::new(Budget::Frames(1))
+            .run_scene("stubborn", &mut Stubborn, &mut SceneRunner::new(), &mut terminal(), &mut report)
+            .unwrap();
+
+        assert_eq!(report.stages.len(), MAX_STAGES);
+    }
+
+    #[test]
+    fn test_report_lists_every_stage() {
+        let registry = SceneRegistry::new()
+            .register("text", Benchmark::new)
+            .register("compute", || Stages { busy: 0, frames: 0 });
+
+        let report = AutoRun::new(Budget::Frames(1))
+            .run(&registry, &mut SceneRunner::new(), &mut terminal())
+            .unwrap()
+            .to_string();
+
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[8].starts_with("text               8       1"));
+        assert!(lines[10].starts_with("total              9       9"));
+    }
+}
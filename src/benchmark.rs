@@ -1,54 +1,61 @@
-use esp_idf_svc::hal::delay;
-use esp_idf_svc::hal::gpio::{Gpio0, Input, PinDriver};
-use esp_idf_svc::hal::task::notification::Notification;
-use ratatui::prelude::{Backend, Terminal};
+use ratatui::Frame;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{Block, Paragraph, Widget, Wrap},
 };
-use std::marker::PhantomData;
-use std::thread;
-use std::time::Duration;
 use compact_str::format_compact;
 use ratatui::layout::Margin;
 use crate::catpuccin::CATPPUCCIN;
-use crate::lorem::LOREM_IPSUM;
+use crate::corpus::{self, Length, Words};
 use crate::fps::FpsWidget;
+use std::time::Duration;
+use crate::input::ButtonEvent;
+use crate::instrument;
+use crate::scene::{Scene, SceneExit};
 use crate::header::render_header;
 
+/// Labels of the style modes, in the order a press cycles through them
+const STYLE_MODES: [&str; 8] = ["plain", "accent", "initial", "word", "background", "modifiers", "latin1", "box"];
+
+/// Modifiers the `modifiers` mode cycles through; mousefood draws `BOLD` with `font_bold`
+const MODIFIERS: [Modifier; 6] = [
+    Modifier::empty(),
+    Modifier::BOLD,
+    Modifier::ITALIC,
+    Modifier::UNDERLINED,
+    Modifier::REVERSED,
+    Modifier::BOLD.union(Modifier::UNDERLINED),
+];
+
 #[derive(Debug)]
-pub struct Benchmark<B: Backend> {
+pub struct Benchmark {
     frame_count: u32,
     style_mode: usize,
-    spans_cache: [Vec<Span<'static>>; 4],
-    fps_widget: FpsWidget,
-    _marker: PhantomData<B>,
+    spans_cache: [Vec<Span<'static>>; STYLE_MODES.len()],
 }
 
-impl<B: Backend> Benchmark<B> {
+impl Benchmark {
     pub fn new() -> Self {
-        let spans_cache = [
-            Self::generate_spans_for_mode(0),
-            Self::generate_spans_for_mode(1),
-            Self::generate_spans_for_mode(2),
-            Self::generate_spans_for_mode(3),
-        ];
-        
+        let spans_cache = std::array::from_fn(Self::generate_spans_for_mode);
+
         Self {
             frame_count: 0,
             style_mode: 0,
             spans_cache,
-            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
-            _marker: PhantomData,
         }
     }
 
     fn generate_spans_for_mode(mode: usize) -> Vec<Span<'static>> {
         let text_len = 2048;
-        lorem_ipsum(text_len, 0)
+        let text = match STYLE_MODES[mode] {
+            "latin1" => corpus::LATIN_1,
+            "box" => corpus::BOX_DRAWING,
+            _ => corpus::LOREM,
+        };
+        Words::new(text, Length::Chars(text_len))
             .map(|word| Self::style_word_for_mode(word, mode))
             .collect()
     }
@@ -56,55 +63,56 @@ impl<B: Backend> Benchmark<B> {
     fn style_word_for_mode(word: &str, mode: usize) -> Span<'static> {
         let colors = colors();
 
-        let color = match mode {
-            0 => CATPPUCCIN.text,
-            1 => if word.starts_with('e') { CATPPUCCIN.green } else { CATPPUCCIN.text },
+        let hash: usize = word.chars().map(|c| c as usize).sum();
+
+        let style = match mode {
+            0 => Style::default().fg(CATPPUCCIN.text),
+            1 => Style::default().fg(if word.starts_with('e') { CATPPUCCIN.green } else { CATPPUCCIN.text }),
             2 => {
                 let hash: usize = word.chars().take(1).map(|c| c as usize / 10).sum();
-                colors[hash % colors.len()]
+                Style::default().fg(colors[hash % colors.len()])
             },
-            _ => {
-                let hash: usize = word.chars().map(|c| c as usize).sum();
-                colors[hash % colors.len()]
-            }
+            // spaces keep the default background, so every word is a run of its own
+            4 if word == " " => Style::default(),
+            4 => Style::default().fg(CATPPUCCIN.base).bg(colors[hash % colors.len()]),
+            5 => Style::default().fg(CATPPUCCIN.text).add_modifier(MODIFIERS[hash % MODIFIERS.len()]),
+            _ => Style::default().fg(colors[hash % colors.len()]),
         };
-        
-        Span::styled(word.to_string(), Style::default().fg(color))
-    }
-
-    pub fn run(
-        mut self,
-        terminal: &mut Terminal<B>,
-        notification: &mut Notification,
-        button: &mut PinDriver<Gpio0, Input>,
-    ) -> std::io::Result<()> {
-        button.enable_interrupt().unwrap();
-        loop {
-            if notification.wait(delay::NON_BLOCK).is_some() {
-                self.style_mode = (self.style_mode + 1) % 4;
-                if self.style_mode == 0 {
-                    return Ok(());
-                } else {
-                    thread::sleep(Duration::from_millis(200));
-                    button.enable_interrupt().unwrap();
-                }
-            }
-            self.frame_count += 1;
-            self.fps_widget.fps.tick();
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))
-                .unwrap();
-        }
+
+        Span::styled(word.to_string(), style)
     }
 }
 
-impl<B: Backend> Default for Benchmark<B> {
+impl Default for Benchmark {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<B: Backend> Widget for &Benchmark<B> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+impl Scene for Benchmark {
+    /// A press cycles through the style modes, a double press skips the rest
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press => {
+                self.style_mode = (self.style_mode + 1) % STYLE_MODES.len();
+                (self.style_mode == 0).then_some(SceneExit::Next)
+            }
+            ButtonEvent::DoublePress => Some(SceneExit::Next),
+            ButtonEvent::LongPress => Some(SceneExit::Quit),
+        }
+    }
+
+    fn mode(&self) -> Option<&'static str> {
+        STYLE_MODES.get(self.style_mode).copied()
+    }
+
+    fn update(&mut self, _elapsed: Duration) {
+        self.frame_count += 1;
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
         let layout = Layout::vertical([
             Constraint::Length(3),
             Constraint::Percentage(100),
@@ -113,13 +121,13 @@ impl<B: Backend> Widget for &Benchmark<B> {
 
         self.render_header(layout[0], buf);
         self.render_benchmark(layout[1], buf);
-        self.render_footer(layout[2], buf);
+        self.render_footer(layout[2], buf, fps);
     }
 }
 
-impl<B: Backend> Benchmark<B> {
+impl Benchmark {
     fn render_header(&self, area: Rect, buf: &mut Buffer) {
-        let title = format_compact!("Text Stress Test [{}]", self.style_mode + 1);
+        let title = format_compact!("Text Stress Test [{} {}]", self.style_mode + 1, STYLE_MODES[self.style_mode]);
         render_header(area, buf, &title, CATPPUCCIN.green);
     }
 
@@ -138,27 +146,19 @@ impl<B: Backend> Benchmark<B> {
         paragraph.render(area, buf);
     }
 
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
         use ratatui::layout::Margin;
         
         let fps_area = area.inner(Margin::new(8, 0));
-        self.fps_widget.render(fps_area, buf);
-    }
-}
+        fps.render(fps_area, buf);
 
-fn lorem_ipsum(len: usize, word_offset: usize) -> impl Iterator<Item = &'static str> {
-    let mut acc = 0;
-    
-    LOREM_IPSUM
-        .split(" ")
-        .cycle()
-        .skip(word_offset)
-        .flat_map(|w| [w, " "].into_iter())
-        .take_while(move |w| {
-            let is_within_screen = acc <= len;
-            acc += w.len();
-            is_within_screen
-        })
+        // what the previous frame handed to the backend; styles split the text into more runs.
+        // Right-aligned past the fps text, so that it doesn't restyle it
+        let draw = instrument::last_frame();
+        let text = format_compact!("{} cells {} runs", draw.cells, draw.runs);
+        let width = (text.len() as u16).min(fps_area.width.saturating_sub(FpsWidget::TEXT_WIDTH + 1));
+        buf.set_stringn(fps_area.right() - width, fps_area.y, text.as_str(), usize::from(width), Style::default().fg(CATPPUCCIN.subtext0));
+    }
 }
 
 fn colors() -> [Color; 16] {
@@ -180,4 +180,73 @@ fn colors() -> [Color; 16] {
         CATPPUCCIN.rosewater,
         CATPPUCCIN.subtext1,
     ]
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use crate::input::ScriptedInput;
+    use crate::scene::SceneRunner;
+
+    fn header(terminal: &Terminal<TestBackend>) -> String {
+        let buf = terminal.backend().buffer();
+        (0..buf.area.width)
+            .map(|x| buf[(x, 0)].symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_press_cycles_style_modes_then_exits() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new();
+        for frame in [1, 2, 3, 4, 5, 6, 7, 9] {
+            input = input.at(frame, ButtonEvent::Press);
+        }
+
+        let exit = SceneRunner::new().run(&mut Benchmark::new(), &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Next);
+        assert!(input.is_exhausted());
+        assert_eq!(input.polls(), 10);
+        assert!(header(&terminal).contains("Text Stress Test [8 box]"));
+    }
+
+    #[test]
+    fn test_double_press_skips_remaining_style_modes() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new()
+            .at(1, ButtonEvent::Press)
+            .at(3, ButtonEvent::DoublePress);
+
+        let exit = SceneRunner::new().run(&mut Benchmark::new(), &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Next);
+        assert_eq!(input.polls(), 4);
+        assert!(header(&terminal).contains("Text Stress Test [2 accent]"));
+    }
+
+    #[test]
+    fn test_long_press_quits() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new()
+            .at(2, ButtonEvent::LongPress);
+
+        let exit = SceneRunner::new().run(&mut Benchmark::new(), &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Quit);
+        assert_eq!(input.polls(), 3);
+    }
+
+    #[test]
+    fn test_extra_modes_style_words() {
+        let benchmark = Benchmark::new();
+        let words = |mode: &str| &benchmark.spans_cache[STYLE_MODES.iter().position(|&m| m == mode).unwrap()];
+
+        assert!(words("background").iter().any(|span| span.style.bg.is_some()));
+        assert!(words("modifiers").iter().any(|span| span.style.add_modifier.contains(Modifier::BOLD)));
+        assert!(words("latin1").iter().any(|span| span.content.contains('é')));
+        assert!(words("box").iter().all(|span| !span.content.is_ascii() || span.content == " "));
+    }
+}
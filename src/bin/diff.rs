@@ -0,0 +1,89 @@
+//! Compares two result files exported by the suite, e.g. before and after a
+//! mousefood change, and exits with status 1 if anything regressed.
+//!
+//! Both files may be raw serial logs: the JSON Lines or CSV records are picked
+//! out of them. Changes within `--threshold` percent (default: 5) count as noise.
+//!
+//! ```bash
+//! cargo run --target x86_64-unknown-linux-gnu --features host --bin mousefood-diff -- before.log after.log --threshold 3
+//! ```
+
+use std::fs;
+use std::process;
+use mousefood_benchmark::diff::Diff;
+use mousefood_benchmark::export::{parse_records, ExportedRecord};
+
+struct Args {
+    before: String,
+    after: String,
+    threshold: f64,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut files = Vec::new();
+        let mut threshold = 5.0;
+
+        let mut it = std::env::args().skip(1);
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "--threshold" => threshold = it.next()
+                    .and_then(|t| t.parse().ok())
+                    .filter(|t: &f64| *t >= 0.0)
+                    .unwrap_or_else(|| usage()),
+                _ if arg.starts_with("--") => usage(),
+                _ => files.push(arg),
+            }
+        }
+
+        let [before, after] = <[String; 2]>::try_from(files).unwrap_or_else(|_| usage());
+        Args { before, after, threshold }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mousefood-diff BEFORE AFTER [--threshold PERCENT]");
+    process::exit(2);
+}
+
+fn read(path: &str) -> Vec<ExportedRecord> {
+    let text = fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("{path}: {err}");
+        process::exit(2);
+    });
+
+    let records = parse_records(&text).unwrap_or_else(|err| {
+        eprintln!("{path}: {err}");
+        process::exit(2);
+    });
+
+    if records.is_empty() {
+        eprintln!("{path}: no exported records found");
+        process::exit(2);
+    }
+    records
+}
+
+fn describe(label: &str, path: &str, records: &[ExportedRecord]) {
+    let first = &records[0];
+    println!("{label}: {path} (run {}, {}, {})", first.run_id, first.profile, first.font);
+}
+
+fn main() {
+    let args = Args::parse();
+    let before = read(&args.before);
+    let after = read(&args.after);
+
+    describe("before", &args.before, &before);
+    describe("after ", &args.after, &after);
+    if before[0].profile != after[0].profile || before[0].font != after[0].font {
+        eprintln!("warning: the runs differ in build profile or font");
+    }
+
+    let diff = Diff::new(&before, &after, args.threshold);
+    print!("{diff}");
+
+    if diff.regressions() > 0 {
+        process::exit(1);
+    }
+}
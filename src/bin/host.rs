@@ -0,0 +1,128 @@
+//! Runs every benchmark scene on the host against an in-memory framebuffer.
+//!
+//! This is the firmware's unattended mode: every scene, text style mode and
+//! microbenchmark stage runs for `--frames` frames (or `--secs` seconds), then
+//! the consolidated report is printed. `--scenes` picks and orders the scenes
+//! by their registry name. With `--dump`, the last frame of every scene is
+//! written as a PPM image. With `--export jsonl` or `--export csv`, the
+//! machine-readable records follow the report on stdout. `--fps-mode ms` or
+//! `--fps-mode p99` changes what the on-screen fps counter shows.
+//!
+//! ```bash
+//! cargo run --target x86_64-unknown-linux-gnu --features host --bin mousefood-host -- --frames 500 --dump out/
+//! ```
+
+use std::fs::File;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::Size;
+use embedded_graphics_unicodefonts::{mono_6x10_optimized_atlas, mono_6x13_bold_atlas};
+use mousefood::prelude::*;
+use mousefood_benchmark::autorun::{AutoRun, Budget};
+use mousefood_benchmark::export::{Exporter, Format, RunInfo};
+use mousefood_benchmark::fps::FpsMode;
+use mousefood_benchmark::framebuffer::Framebuffer;
+use mousefood_benchmark::instrument::InstrumentedBackend;
+use mousefood_benchmark::throughput::{CountingTarget, SpiModel};
+use mousefood_benchmark::report::Report;
+use mousefood_benchmark::scene::{SceneRegistry, SceneRunner};
+use mousefood_benchmark::DISPLAY_SIZE;
+use ratatui::Terminal;
+use ratatui::layout::Layout;
+
+struct Args {
+    budget: Budget,
+    scenes: Option<String>,
+    dump_dir: Option<PathBuf>,
+    export: Option<Format>,
+    fps_mode: FpsMode,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = Args { budget: Budget::Frames(300), scenes: None, dump_dir: None, export: None, fps_mode: FpsMode::Fps };
+
+        let mut it = std::env::args().skip(1);
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "--frames" => args.budget = Budget::Frames(it.next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())),
+                "--secs" => args.budget = Budget::Time(it.next()
+                    .and_then(|n| n.parse().ok())
+                    .map(Duration::from_secs_f32)
+                    .unwrap_or_else(|| usage())),
+                "--scenes" => args.scenes = Some(it.next().unwrap_or_else(|| usage())),
+                "--dump" => args.dump_dir = Some(it.next().unwrap_or_else(|| usage()).into()),
+                "--export" => args.export = Some(it.next()
+                    .and_then(|f| f.parse().ok())
+                    .unwrap_or_else(|| usage())),
+                "--fps-mode" => args.fps_mode = it.next()
+                    .and_then(|m| m.parse().ok())
+                    .unwrap_or_else(|| usage()),
+                _ => usage(),
+            }
+        }
+
+        args
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mousefood-host [--frames N | --secs S] [--scenes NAME,..] [--dump DIR] [--export jsonl|csv] [--fps-mode fps|ms|p99]");
+    process::exit(2);
+}
+
+fn main() {
+    let args = Args::parse();
+    let registry = match &args.scenes {
+        Some(names) => SceneRegistry::builtin().select(names).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(2);
+        }),
+        None => SceneRegistry::builtin(),
+    };
+
+    if let Some(dir) = &args.dump_dir {
+        std::fs::create_dir_all(dir).expect("failed to create dump directory");
+    }
+
+    // the firmware rotates the panel by 90°
+    let framebuffer = Framebuffer::new(Size::new(DISPLAY_SIZE.1 as _, DISPLAY_SIZE.0 as _));
+    // estimates what the firmware's SPI bus would take for the same frames
+    let mut display = CountingTarget::new(framebuffer, SpiModel::default());
+
+    let mut config = EmbeddedBackendConfig::default();
+    config.font_regular = mono_6x10_optimized_atlas();
+    config.font_bold = Some(mono_6x13_bold_atlas());
+
+    let backend = InstrumentedBackend::new(EmbeddedBackend::new(&mut display, config));
+    let mut terminal = Terminal::new(backend).unwrap();
+    Layout::init_cache(NonZeroUsize::new(20).unwrap());
+
+    let autorun = AutoRun::new(args.budget);
+    let mut runner = SceneRunner::new().with_fps_mode(args.fps_mode);
+    let mut report = Report::default();
+    for (name, create) in registry.iter() {
+        autorun.run_scene(name, create().as_mut(), &mut runner, &mut terminal, &mut report).unwrap();
+
+        if let Some(dir) = &args.dump_dir {
+            let path = dir.join(format!("{name}.ppm"));
+            let file = File::create(&path).expect("failed to create dump file");
+            terminal.backend().inner().display().inner().write_ppm(io::BufWriter::new(file)).unwrap();
+        }
+    }
+
+    print!("{report}");
+
+    if let Some(format) = args.export {
+        println!();
+        Exporter::new(format, RunInfo::new("mono_6x10_optimized_atlas"))
+            .write_report(&report, &mut io::stdout().lock())
+            .unwrap();
+    }
+}
@@ -0,0 +1,60 @@
+//! Renders the static screens on the host and writes their snapshots for the firmware.
+//!
+//! Every entry of `snapshot::BAKED` is rendered in the panel's terminal size and
+//! written to `DIR` (default: `src/snapshots`), where the firmware includes it with
+//! `include_bytes!`. Run it after changing one of those screens; the library tests
+//! fail while a snapshot is stale. With `--check`, nothing is written and the exit
+//! status is 1 if any snapshot differs.
+//!
+//! ```bash
+//! cargo run --target x86_64-unknown-linux-gnu --features host --bin mousefood-snapshot
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use mousefood_benchmark::snapshot::{Snapshot, BAKED};
+
+fn usage() -> ! {
+    eprintln!("usage: mousefood-snapshot [--check] [DIR]");
+    process::exit(2);
+}
+
+fn main() {
+    let mut check = false;
+    let mut dir = PathBuf::from("src/snapshots");
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            flag if flag.starts_with('-') => usage(),
+            path => dir = path.into(),
+        }
+    }
+
+    let mut stale = 0;
+    for baked in BAKED {
+        let bytes = baked.capture();
+        let snapshot = Snapshot::new(&bytes).expect("captures always decode");
+        let path = dir.join(baked.file);
+        let current = fs::read(&path).is_ok_and(|old| old == bytes);
+
+        if check {
+            if !current {
+                eprintln!("{} is stale", path.display());
+                stale += 1;
+            }
+            continue;
+        }
+
+        if let Err(err) = fs::write(&path, &bytes) {
+            eprintln!("{}: {err}", path.display());
+            process::exit(1);
+        }
+        println!("{}: {} cells, {} bytes{}", path.display(), snapshot.len(), bytes.len(),
+            if current { "" } else { " (updated)" });
+    }
+
+    if stale > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,347 @@
+//! Storage formats for the cells a [`WormBuffer`](crate::worm_buffer::WormBuffer) captures.
+//!
+//! [`CellList`] keeps every cell as ratatui stores it, [`RunList`] packs them into
+//! row runs with a shared style palette; the `worm_buffer` scene compares the two.
+//! Snapshots are a [`RunList`] written out as is.
+
+use std::fmt;
+use std::mem::size_of;
+use compact_str::CompactString;
+use ratatui::buffer::{Buffer, Cell, CellDiffOption};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Modifier, Style};
+
+/// Cells recorded at their positions, replayed in the order they were pushed
+pub trait CellStore: Default + Clone + fmt::Debug {
+    /// Records `cell` at `pos`
+    fn push(&mut self, pos: Position, cell: &Cell);
+
+    /// Writes every recorded cell into `buf`
+    fn replay(&self, buf: &mut Buffer);
+
+    /// Drops the recorded cells inside `area`
+    fn remove_area(&mut self, area: Rect);
+
+    /// Appends the cells recorded in `other`
+    fn extend(&mut self, other: &Self);
+
+    /// Every recorded cell with its position, in the order they were pushed
+    fn cells(&self) -> impl Iterator<Item = (Position, Cell)> + '_;
+
+    /// Number of recorded cells
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes allocated on the heap, including unused capacity
+    fn heap_bytes(&self) -> usize;
+}
+
+/// Every cell with its position, as ratatui stores it
+#[derive(Debug, Default, Clone)]
+pub struct CellList(Vec<(Position, Cell)>);
+
+impl CellStore for CellList {
+    fn push(&mut self, pos: Position, cell: &Cell) {
+        self.0.push((pos, cell.clone()));
+    }
+
+    fn replay(&self, buf: &mut Buffer) {
+        self.0
+            .iter()
+            .cloned()
+            .for_each(|(pos, cell)| buf[pos] = cell);
+    }
+
+    fn remove_area(&mut self, area: Rect) {
+        self.0.retain(|(pos, _)| !area.contains(*pos));
+    }
+
+    fn extend(&mut self, other: &Self) {
+        self.0.extend_from_slice(&other.0);
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        self.0.iter().cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        // symbols longer than a `CompactString` itself live on the heap
+        let symbols: usize = self.0.iter()
+            .map(|(_, cell)| cell.symbol().len())
+            .filter(|&len| len > size_of::<CompactString>())
+            .sum();
+
+        self.0.capacity() * size_of::<(Position, Cell)>() + symbols
+    }
+}
+
+/// Cells packed into runs of consecutive positions on a row
+///
+/// Each cell takes four bytes plus its symbol's UTF-8 bytes; the styles are
+/// kept once each in a palette, which is searched linearly on capture, as a
+/// screen rarely has more than a few dozen.
+#[derive(Debug, Default, Clone)]
+pub struct RunList {
+    runs: Vec<Run>,
+    glyphs: Vec<Glyph>,
+    /// Symbols of all glyphs, back to back
+    symbols: String,
+    palette: Vec<CellStyle>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    x: u16,
+    y: u16,
+    len: u16,
+    /// Index of the run's first glyph
+    glyph: u32,
+    /// Offset of the run's first symbol
+    symbol: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Glyph {
+    /// Index into the palette
+    style: u16,
+    /// Length of the symbol in bytes
+    len: u16,
+}
+
+/// Everything of a cell but its symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellStyle {
+    style: Style,
+    skip: bool,
+    diff_option: CellDiffOption,
+}
+
+impl CellStyle {
+    // `skip` is deprecated, but still part of a cell and its equality
+    #[allow(deprecated)]
+    fn of(cell: &Cell) -> Self {
+        Self {
+            style: cell.style(),
+            skip: cell.skip,
+            diff_option: cell.diff_option,
+        }
+    }
+
+    #[allow(deprecated)]
+    fn apply(&self, cell: &mut Cell, symbol: &str) {
+        // `set_style` adds to the modifiers rather than replacing them
+        cell.modifier = Modifier::empty();
+        cell.set_symbol(symbol)
+            .set_style(self.style)
+            .set_skip(self.skip)
+            .set_diff_option(self.diff_option);
+    }
+}
+
+impl RunList {
+    /// Number of runs the cells form
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Number of distinct styles
+    pub fn styles(&self) -> usize {
+        self.palette.len()
+    }
+
+    /// The distinct styles, each as a blank cell, in the order the glyphs index them
+    pub(crate) fn palette(&self) -> impl ExactSizeIterator<Item = Cell> + '_ {
+        self.palette.iter().map(|style| {
+            let mut cell = Cell::default();
+            style.apply(&mut cell, " ");
+            cell
+        })
+    }
+
+    /// Each run's first position and length
+    pub(crate) fn run_extents(&self) -> impl ExactSizeIterator<Item = (Position, u16)> + '_ {
+        self.runs.iter().map(|run| (Position::new(run.x, run.y), run.len))
+    }
+
+    /// Each cell's style index and symbol length in bytes, in run order
+    pub(crate) fn glyphs(&self) -> impl ExactSizeIterator<Item = (u16, u16)> + '_ {
+        self.glyphs.iter().map(|glyph| (glyph.style, glyph.len))
+    }
+
+    /// Symbols of all cells, back to back
+    pub(crate) fn symbols(&self) -> &str {
+        &self.symbols
+    }
+
+    fn push_styled(&mut self, pos: Position, style: CellStyle, symbol: &str) {
+        // neighbouring cells mostly share a style, so the last one is tried first
+        let style = match self.glyphs.last() {
+            Some(last) if self.palette[usize::from(last.style)] == style => usize::from(last.style),
+            _ => match self.palette.iter().position(|s| *s == style) {
+                Some(i) => i,
+                None => {
+                    self.palette.push(style);
+                    self.palette.len() - 1
+                }
+            },
+        };
+
+        match self.runs.last_mut() {
+            Some(run) if run.y == pos.y && u32::from(run.x) + u32::from(run.len) == u32::from(pos.x) => {
+                run.len += 1;
+            }
+            _ => self.runs.push(Run {
+                x: pos.x,
+                y: pos.y,
+                len: 1,
+                glyph: self.glyphs.len() as u32,
+                symbol: self.symbols.len() as u32,
+            }),
+        }
+
+        self.glyphs.push(Glyph {
+            style: u16::try_from(style).expect("at most 65536 styles per capture"),
+            len: symbol.len() as u16,
+        });
+        self.symbols.push_str(symbol);
+    }
+
+    /// Every recorded cell, as its position, style and symbol
+    fn entries(&self) -> impl Iterator<Item = (Position, CellStyle, &str)> {
+        self.runs.iter().flat_map(move |run| {
+            let glyphs = &self.glyphs[run.glyph as usize..][..usize::from(run.len)];
+            let mut offset = run.symbol as usize;
+            (run.x..).zip(glyphs).map(move |(x, glyph)| {
+                let start = offset;
+                offset += usize::from(glyph.len);
+                (Position::new(x, run.y), self.palette[usize::from(glyph.style)], &self.symbols[start..offset])
+            })
+        })
+    }
+}
+
+impl CellStore for RunList {
+    fn push(&mut self, pos: Position, cell: &Cell) {
+        self.push_styled(pos, CellStyle::of(cell), cell.symbol());
+    }
+
+    fn replay(&self, buf: &mut Buffer) {
+        for run in &self.runs {
+            let start = buf.index_of(run.x, run.y);
+            let cells = &mut buf.content[start..][..usize::from(run.len)];
+            let glyphs = &self.glyphs[run.glyph as usize..][..usize::from(run.len)];
+
+            let mut offset = run.symbol as usize;
+            for (cell, glyph) in cells.iter_mut().zip(glyphs) {
+                let end = offset + usize::from(glyph.len);
+                self.palette[usize::from(glyph.style)].apply(cell, &self.symbols[offset..end]);
+                offset = end;
+            }
+        }
+    }
+
+    fn remove_area(&mut self, area: Rect) {
+        let old = std::mem::take(self);
+        old.entries()
+            .filter(|(pos, _, _)| !area.contains(*pos))
+            .for_each(|(pos, style, symbol)| self.push_styled(pos, style, symbol));
+    }
+
+    fn extend(&mut self, other: &Self) {
+        other.entries().for_each(|(pos, style, symbol)| self.push_styled(pos, style, symbol));
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        self.entries().map(|(pos, style, symbol)| {
+            let mut cell = Cell::default();
+            style.apply(&mut cell, symbol);
+            (pos, cell)
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.runs.capacity() * size_of::<Run>()
+            + self.glyphs.capacity() * size_of::<Glyph>()
+            + self.symbols.capacity()
+            + self.palette.capacity() * size_of::<CellStyle>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Stylize};
+    use ratatui::text::Line;
+    use ratatui::widgets::Widget;
+
+    fn styled_buffer() -> Buffer {
+        let area = Rect::new(0, 0, 12, 3);
+        let mut buf = Buffer::empty(area);
+        Line::from(vec!["red".red(), " ".into(), "bold".bold().on_blue(), "┃é".into()])
+            .render(Rect::new(1, 0, 11, 1), &mut buf);
+        Line::from("⣿⣿".yellow().underlined()).render(Rect::new(8, 2, 4, 1), &mut buf);
+        buf[(0, 1)].set_skip(true);
+        buf
+    }
+
+    fn capture<S: CellStore>(buf: &Buffer) -> S {
+        let mut store = S::default();
+        buf.area.positions()
+            .filter(|&pos| buf[pos] != Cell::EMPTY)
+            .for_each(|pos| store.push(pos, &buf[pos]));
+        store
+    }
+
+    #[test]
+    fn test_run_list_replays_like_cell_list() {
+        let buf = styled_buffer();
+        let cells: CellList = capture(&buf);
+        let runs: RunList = capture(&buf);
+        assert_eq!(runs.len(), cells.len());
+        // the blank between the words isn't captured, so it splits the first row in two
+        assert_eq!(runs.runs(), 4);
+
+        // replayed onto a dirty buffer, every modifier is replaced rather than added to
+        let mut expected = Buffer::filled(buf.area, Cell::new("x").set_fg(Color::Green).set_style(Modifier::ITALIC).clone());
+        let mut actual = expected.clone();
+        cells.replay(&mut expected);
+        runs.replay(&mut actual);
+        assert_eq!(actual, expected);
+        assert!(runs.heap_bytes() < cells.heap_bytes() / 2);
+    }
+
+    #[test]
+    fn test_run_list_remove_area_and_extend() {
+        let buf = styled_buffer();
+        let area = Rect::new(2, 0, 3, 3);
+        let mut cells: CellList = capture(&buf);
+        let mut runs: RunList = capture(&buf);
+        cells.remove_area(area);
+        runs.remove_area(area);
+        assert_eq!(runs.len(), cells.len());
+
+        let mut other: RunList = capture(&buf);
+        other.extend(&runs);
+
+        let mut expected = Buffer::empty(buf.area);
+        let mut actual = expected.clone();
+        cells.replay(&mut expected);
+        runs.replay(&mut actual);
+        assert_eq!(actual, expected);
+
+        let mut all = Buffer::empty(buf.area);
+        other.replay(&mut all);
+        assert_eq!(all, buf);
+    }
+}
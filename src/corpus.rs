@@ -0,0 +1,187 @@
+//! Sample texts for the text and glyph benchmarks, and word streams of a set length.
+//!
+//! Only [`LOREM`], [`ENGLISH`], [`LATIN_1`], [`BOX_DRAWING`] and [`BRAILLE`] are
+//! fully covered by `mono_6x10_optimized_atlas`; the Greek and Cyrillic texts
+//! show where a font falls back to its replacement glyph.
+
+/// A named sample text; words are separated by whitespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text {
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+pub const LOREM: Text = Text {
+    name: "lorem",
+    text: "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.",
+};
+
+pub const ENGLISH: Text = Text {
+    name: "english",
+    text: "The quick brown fox jumps over the lazy dog. Pack my box with five dozen liquor jugs! \
+        Battery at 87%, uptime 3d 14h, 12 tasks running; press S1 to continue or hold it to return.",
+};
+
+/// German, French, Spanish and Portuguese, all within Latin-1
+pub const LATIN_1: Text = Text {
+    name: "latin1",
+    text: "Où êtes-vous allé cet été? À la forêt près du château, où l'élève mangeait des crêpes. \
+        Größere Bäume wachsen über den Flüssen, während Jäger schön müde heimkehren. \
+        El niño pidió café y jamón en la montaña; ¡qué señor tan pequeño! Ação, coração e pão são três.",
+};
+
+pub const GREEK: Text = Text {
+    name: "greek",
+    text: "Ξεσκεπάζω την ψυχοφθόρα βδελυγμία. Η γρήγορη καφέ αλεπού πηδά πάνω από τον τεμπέλη σκύλο. \
+        Καλημέρα, τι κάνεις σήμερα; Η μπαταρία είναι στο 87%.",
+};
+
+pub const CYRILLIC: Text = Text {
+    name: "cyrillic",
+    text: "Съешь же ещё этих мягких французских булок, да выпей чаю. \
+        Широкая электрификация южных губерний даст мощный толчок подъёму сельского хозяйства.",
+};
+
+/// Words of box-drawing and block characters, as in borders, tables and bar charts
+pub const BOX_DRAWING: Text = Text {
+    name: "box",
+    text: "┌──┬──┐ │░░│▒▒│ ├──┼──┤ │▓▓│██│ └──┴──┘ ╔══╦══╗ ║▀▄║▌▐║ ╠══╬══╣ ╚══╩══╝ \
+        ▁▂▃▄▅▆▇█ █▇▆▅▄▃▂▁ ─┼─ ━╋━ ╭──╮ ╰──╯ ▖▗▘▝ ▙▛▜▟ ┃┃ ┆┊ ╱╲╳",
+};
+
+/// Rows of braille art, as drawn by canvas widgets
+pub const BRAILLE: Text = Text {
+    name: "braille",
+    text: "⠀⠀⣠⣴⣶⣿⣿⣶⣦⣄⠀⠀ ⠀⣴⣿⡿⠛⠉⠉⠛⢿⣿⣦⠀ ⣸⣿⠏⠀⠀⠀⠀⠀⠀⠹⣿⣇ ⣿⣿⠀⠀⠀⠀⠀⠀⠀⠀⣿⣿ \
+        ⢹⣿⣆⠀⠀⠀⠀⠀⠀⣰⣿⡏ ⠀⠻⣿⣷⣤⣀⣀⣤⣾⣿⠟⠀ ⠀⠀⠙⠻⠿⣿⣿⠿⠟⠋⠀⠀ ⡀⢀⡠⠔⠊⠁⠈⠑⠢⢄⡀⢀ ⠈⠁⠀⠀⠀⠀⠀⠀⠀⠀⠈⠁",
+};
+
+/// Every text, in the order the benchmarks list them
+pub const ALL: [Text; 7] = [LOREM, ENGLISH, LATIN_1, GREEK, CYRILLIC, BOX_DRAWING, BRAILLE];
+
+/// Finds a text of [`ALL`] by name
+pub fn by_name(name: &str) -> Option<Text> {
+    ALL.into_iter().find(|text| text.name == name)
+}
+
+/// How the length of a word stream is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    /// UTF-8 bytes, as stored in a span
+    Bytes(usize),
+    /// Characters, each taking one cell with the monospace fonts
+    Chars(usize),
+}
+
+impl Length {
+    fn target(self) -> usize {
+        match self {
+            Length::Bytes(n) | Length::Chars(n) => n,
+        }
+    }
+
+    fn of(self, s: &str) -> usize {
+        match self {
+            Length::Bytes(_) => s.len(),
+            Length::Chars(_) => s.chars().count(),
+        }
+    }
+
+    /// The longest prefix of `s` at most `n` long
+    fn prefix(self, s: &str, n: usize) -> &str {
+        match self {
+            Length::Bytes(_) => {
+                let end = (0..=n.min(s.len())).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0);
+                &s[..end]
+            }
+            Length::Chars(_) => s.char_indices().nth(n).map_or(s, |(i, _)| &s[..i]),
+        }
+    }
+}
+
+/// Words of a [`Text`] and the spaces between them, cycling through the text
+/// until the stream reaches its [`Length`]
+///
+/// The last word is cut to fit, so a stream measured in characters is exactly
+/// as long as asked; one measured in bytes may be up to three bytes shorter,
+/// rather than split a character.
+#[derive(Debug, Clone)]
+pub struct Words {
+    words: std::iter::Cycle<std::str::SplitWhitespace<'static>>,
+    length: Length,
+    remaining: usize,
+    space_next: bool,
+}
+
+impl Words {
+    pub fn new(text: Text, length: Length) -> Self {
+        Self {
+            words: text.text.split_whitespace().cycle(),
+            length,
+            remaining: length.target(),
+            space_next: false,
+        }
+    }
+
+    /// Starts the stream `n` words into the text
+    pub fn skip_words(mut self, n: usize) -> Self {
+        for _ in 0..n {
+            self.words.next();
+        }
+        self
+    }
+}
+
+impl Iterator for Words {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<&'static str> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let piece = if self.space_next { " " } else { self.words.next()? };
+        self.space_next = !self.space_next;
+
+        let len = self.length.of(piece);
+        if len <= self.remaining {
+            self.remaining -= len;
+            return Some(piece);
+        }
+
+        let piece = self.length.prefix(piece, self.remaining);
+        self.remaining = 0;
+        (!piece.is_empty()).then_some(piece)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_streams_are_exact() {
+        for text in ALL {
+            for len in [0, 1, 7, 100, 2048] {
+                let stream: String = Words::new(text, Length::Chars(len)).collect();
+                assert_eq!(stream.chars().count(), len, "{}", text.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_byte_streams_never_split_characters() {
+        let stream: String = Words::new(GREEK, Length::Bytes(101)).collect();
+        assert_eq!(stream.len(), 100);
+        assert!(stream.starts_with("Ξεσκεπάζω την"));
+
+        let stream: String = Words::new(LOREM, Length::Bytes(17)).skip_words(2).collect();
+        assert_eq!(stream, "dolor sit amet, c");
+    }
+
+    #[test]
+    fn test_by_name() {
+        assert_eq!(by_name("cyrillic"), Some(CYRILLIC));
+        assert_eq!(by_name("klingon"), None);
+    }
+}
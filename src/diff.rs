@@ -0,0 +1,323 @@
+//! Comparison of two exported result files, as done by the `mousefood-diff` host tool.
+//!
+//! Microbenchmarks are compared by their median time per iteration, scenes by
+//! their average frame rate per stage. Records that appear several times in one
+//! file, e.g. from several autorun rounds, are reduced to their median.
+
+use std::fmt;
+use crate::export::ExportedRecord;
+
+/// Which direction of change is an improvement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Better {
+    Lower,
+    Higher,
+}
+
+/// A single comparable number from a result file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    pub section: String,
+    pub label: String,
+    pub better: Better,
+    pub value: f64,
+}
+
+/// Title of the table section a record belongs to
+fn section(record: &ExportedRecord) -> String {
+    if !record.is_microbench() {
+        return "fps".into();
+    }
+
+    match record.scene.as_str() {
+        "compute" => "compute (ns/iter)".into(),
+        "glyph_mapping" => "glyph mapping: str vs atlas (ns/iter)".into(),
+        "string_ops" => "string ops: CompactString vs EmbeddedStr (ns/iter)".into(),
+        "worm_buffer" => "worm buffer: cell list vs runs (ns/iter)".into(),
+        scene => format!("{scene} (ns/iter)"),
+    }
+}
+
+/// Extracts the metrics of `records`, in order of first appearance
+pub fn metrics(records: &[ExportedRecord]) -> Vec<Metric> {
+    let mut grouped: Vec<(Metric, Vec<f64>)> = Vec::new();
+    for record in records {
+        let (label, better, value) = if record.is_microbench() {
+            (format!("{} {}", record.mode, record.name), Better::Lower, record.ns_per_iter())
+        } else {
+            (format!("{} {}", record.scene, record.mode), Better::Higher, record.fps())
+        };
+        let section = section(record);
+
+        match grouped.iter_mut().find(|(m, _)| m.section == section && m.label == label) {
+            Some((_, values)) => values.push(value),
+            None => grouped.push((Metric { section, label, better, value }, vec![value])),
+        }
+    }
+
+    grouped.into_iter()
+        .map(|(metric, mut values)| {
+            values.sort_unstable_by(f64::total_cmp);
+            let mid = values.len() / 2;
+            let value = if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] };
+            Metric { value, ..metric }
+        })
+        .collect()
+}
+
+/// Outcome of comparing one metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Worse by more than the threshold
+    Regression,
+    /// Better by more than the threshold
+    Improvement,
+    /// Within the threshold
+    Unchanged,
+    /// Only in the second file
+    Added,
+    /// Only in the first file
+    Removed,
+}
+
+/// One line of the comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub section: String,
+    pub label: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    pub verdict: Verdict,
+}
+
+impl Row {
+    /// Absolute change, `after - before`
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.after? - self.before?)
+    }
+
+    /// Change relative to `before`, in percent; `None` for a zero baseline
+    pub fn relative(&self) -> Option<f64> {
+        let before = self.before?;
+        (before != 0.0).then(|| self.delta().unwrap_or(0.0) / before * 100.0)
+    }
+}
+
+/// Per-metric comparison of two result files
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// Noise threshold, in percent
+    pub threshold: f64,
+    pub rows: Vec<Row>,
+}
+
+impl Diff {
+    /// Compares `after` against `before`, flagging changes beyond `threshold` percent
+    pub fn new(before: &[ExportedRecord], after: &[ExportedRecord], threshold: f64) -> Self {
+        let before = metrics(before);
+        let after = metrics(after);
+        let find = |metrics: &[Metric], m: &Metric| {
+            metrics.iter().find(|o| o.section == m.section && o.label == m.label).map(|o| o.value)
+        };
+
+        let mut rows: Vec<Row> = before.iter()
+            .map(|m| Self::row(m, Some(m.value), find(&after, m), threshold))
+            .collect();
+
+        for m in after.iter().filter(|m| find(&before, m).is_none()) {
+            let row = Self::row(m, None, Some(m.value), threshold);
+            // keep the sections together
+            let idx = rows.iter().rposition(|r| r.section == m.section).map_or(rows.len(), |i| i + 1);
+            rows.insert(idx, row);
+        }
+
+        Self { threshold, rows }
+    }
+
+    fn row(metric: &Metric, before: Option<f64>, after: Option<f64>, threshold: f64) -> Row {
+        let mut row = Row {
+            section: metric.section.clone(),
+            label: metric.label.clone(),
+            before,
+            after,
+            verdict: Verdict::Unchanged,
+        };
+
+        // from a zero baseline, any change is beyond the threshold
+        let change = row.relative().or_else(|| {
+            row.delta().map(|delta| if delta == 0.0 { 0.0 } else { delta.signum() * f64::INFINITY })
+        });
+
+        row.verdict = match (before, after, change) {
+            (None, _, _) => Verdict::Added,
+            (_, None, _) => Verdict::Removed,
+            (_, _, None) => Verdict::Unchanged,
+            (_, _, Some(relative)) => {
+                let worse = match metric.better {
+                    Better::Lower => relative,
+                    Better::Higher => -relative,
+                };
+                if worse > threshold {
+                    Verdict::Regression
+                } else if worse < -threshold {
+                    Verdict::Improvement
+                } else {
+                    Verdict::Unchanged
+                }
+            }
+        };
+        row
+    }
+
+    pub fn regressions(&self) -> usize {
+        self.rows.iter().filter(|r| r.verdict == Verdict::Regression).count()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"));
+
+        let mut section = None;
+        for row in &self.rows {
+            if section != Some(&row.section) {
+                section = Some(&row.section);
+                writeln!(f, "\n{:<26} {:>11} {:>11} {:>11} {:>8}", row.section, "before", "after", "delta", "rel")?;
+            }
+
+            let delta = row.delta().map_or_else(|| "-".to_string(), |d| format!("{d:+.2}"));
+            let relative = row.relative().map_or_else(|| "-".to_string(), |r| format!("{r:+.1}%"));
+            let flag = match row.verdict {
+                Verdict::Regression => "REGRESSION",
+                Verdict::Improvement => "improved",
+                Verdict::Unchanged => "",
+                Verdict::Added => "new",
+                Verdict::Removed => "missing",
+            };
+
+            writeln!(
+                f,
+                "  {:<24} {:>11} {:>11} {:>11} {:>8}  {flag}",
+                row.label,
+                value(row.before),
+                value(row.after),
+                delta,
+                relative,
+            )?;
+        }
+
+        writeln!(f, "\n{} regression(s) beyond ±{}%", self.regressions(), self.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bench(scene: &str, group: &str, name: &str, median_ns: u64) -> ExportedRecord {
+        ExportedRecord {
+            kind: "microbench".into(),
+            scene: scene.into(),
+            mode: group.into(),
+            name: name.into(),
+            iterations: 1000,
+            median_ns,
+            ..Default::default()
+        }
+    }
+
+    fn frames(scene: &str, mode: &str, count: usize, elapsed_ms: u64) -> ExportedRecord {
+        ExportedRecord {
+            kind: "frames".into(),
+            scene: scene.into(),
+            mode: mode.into(),
+            count,
+            elapsed_ns: elapsed_ms * 1_000_000,
+            ..Default::default()
+        }
+    }
+
+    fn verdicts(diff: &Diff) -> Vec<(&str, Verdict)> {
+        diff.rows.iter().map(|r| (r.label.as_str(), r.verdict)).collect()
+    }
+
+    #[test]
+    fn test_flags_changes_beyond_threshold() {
+        let before = [
+            bench("compute", "u32", "ADD", 2000),
+            bench("compute", "u32", "MUL", 2000),
+            bench("compute", "f32", "DIV", 2000),
+            frames("text", "plain", 100, 1000),
+            frames("gauge", "1", 100, 1000),
+        ];
+        let after = [
+            bench("compute", "u32", "ADD", 2080),
+            bench("compute", "u32", "MUL", 2200),
+            bench("compute", "f32", "DIV", 1500),
+            frames("text", "plain", 80, 1000),
+            frames("gauge", "1", 120, 1000),
+        ];
+
+        let diff = Diff::new(&before, &after, 5.0);
+        assert_eq!(verdicts(&diff), [
+            ("u32 ADD", Verdict::Unchanged),
+            ("u32 MUL", Verdict::Regression),
+            ("f32 DIV", Verdict::Improvement),
+            ("text plain", Verdict::Regression),
+            ("gauge 1", Verdict::Improvement),
+        ]);
+        assert_eq!(diff.regressions(), 2);
+        assert!((diff.rows[1].delta().unwrap() - 0.2).abs() < 1e-9);
+        assert!((diff.rows[1].relative().unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_change_from_zero_is_beyond_threshold() {
+        let before = [
+            bench("compute", "u32", "ADD", 0),
+            bench("compute", "u32", "MUL", 0),
+            frames("text", "plain", 0, 1000),
+        ];
+        let after = [
+            bench("compute", "u32", "ADD", 10),
+            bench("compute", "u32", "MUL", 0),
+            frames("text", "plain", 50, 1000),
+        ];
+
+        let diff = Diff::new(&before, &after, 5.0);
+        assert_eq!(verdicts(&diff), [
+            ("u32 ADD", Verdict::Regression),
+            ("u32 MUL", Verdict::Unchanged),
+            ("text plain", Verdict::Improvement),
+        ]);
+        assert_eq!(diff.rows[0].relative(), None);
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let before = [bench("string_ops", "compact", "ascii", 100), bench("compute", "u32", "ADD", 100)];
+        let after = [bench("compute", "u32", "ADD", 100), bench("string_ops", "embedded", "ascii", 100)];
+
+        let diff = Diff::new(&before, &after, 5.0);
+        assert_eq!(verdicts(&diff), [
+            ("compact ascii", Verdict::Removed),
+            ("embedded ascii", Verdict::Added),
+            ("u32 ADD", Verdict::Unchanged),
+        ]);
+        assert_eq!(diff.regressions(), 0);
+        assert!(diff.to_string().contains("string ops: CompactString vs EmbeddedStr"));
+    }
+
+    #[test]
+    fn test_repeated_records_use_the_median() {
+        let records = [
+            frames("stats", "1", 10, 1000),
+            frames("stats", "1", 30, 1000),
+            frames("stats", "1", 20, 1000),
+        ];
+
+        let metrics = metrics(&records);
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].value, 20.0);
+    }
+}
@@ -0,0 +1,314 @@
+use ratatui::Frame;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Style},
+    widgets::Widget,
+};
+use compact_str::{format_compact, CompactString};
+use std::time::Duration;
+use crate::catpuccin::CATPPUCCIN;
+use crate::fps::FpsWidget;
+use crate::header::render_header;
+use crate::input::ButtonEvent;
+use crate::instrument;
+use crate::scene::{Scene, SceneExit};
+
+/// Share of the cells changed every frame, in percent
+pub const RATIOS: [u8; 6] = [0, 1, 10, 25, 50, 100];
+
+/// Labels of the stages: every ratio of a pattern, then the next pattern
+const STAGES: [&str; 24] = [
+    "scattered 0%", "scattered 1%", "scattered 10%", "scattered 25%", "scattered 50%", "scattered 100%",
+    "rows 0%", "rows 1%", "rows 10%", "rows 25%", "rows 50%", "rows 100%",
+    "columns 0%", "columns 1%", "columns 10%", "columns 25%", "columns 50%", "columns 100%",
+    "rect 0%", "rect 1%", "rect 10%", "rect 25%", "rect 50%", "rect 100%",
+];
+
+/// Where the changed cells of a frame are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// Spread over the whole area, in a different place every frame
+    Scattered,
+    /// A contiguous stretch in row order, continuing where the last frame stopped
+    Rows,
+    /// A contiguous stretch in column order, continuing where the last frame stopped
+    Columns,
+    /// A single rectangle with the aspect ratio of the area, moving every frame
+    ///
+    /// Its size is rounded to whole cells, so it changes only about the ratio.
+    Rect,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 4] = [Pattern::Scattered, Pattern::Rows, Pattern::Columns, Pattern::Rect];
+}
+
+/// Synthetic workload changing a fixed share of the screen every frame
+///
+/// Each stage combines a [`Pattern`] with one of the [`RATIOS`]; its summary
+/// is the average number of cells changed and drawn per frame, giving the cost
+/// of mousefood's diffing and drawing as a function of the changed cells.
+#[derive(Debug)]
+pub struct DirtyApp {
+    stage: usize,
+    frame: usize,
+    /// Area the cells were laid out for
+    area: Rect,
+    /// Generation of every cell of the area, row by row; bumped to change it
+    cells: Vec<u8>,
+    /// Frames of the current stage, and the cells they changed and drew
+    frames: usize,
+    changed: usize,
+    drawn: usize,
+}
+
+impl DirtyApp {
+    pub fn new() -> Self {
+        Self {
+            stage: 0,
+            frame: 0,
+            area: Rect::ZERO,
+            cells: Vec::new(),
+            frames: 0,
+            changed: 0,
+            drawn: 0,
+        }
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        Pattern::ALL[self.stage / RATIOS.len()]
+    }
+
+    pub fn ratio(&self) -> u8 {
+        RATIOS[self.stage % RATIOS.len()]
+    }
+
+    fn next_stage(&mut self) -> bool {
+        self.stage = (self.stage + 1) % STAGES.len();
+        self.frames = 0;
+        self.changed = 0;
+        self.drawn = 0;
+        self.stage == 0
+    }
+
+    /// Bumps the cells of this frame's pattern, returning how many changed
+    fn mutate(&mut self) -> usize {
+        let (width, height) = (self.area.width as usize, self.area.height as usize);
+        let len = self.cells.len();
+        let count = (len * self.ratio() as usize + 50) / 100;
+        if count == 0 {
+            return 0;
+        }
+
+        match self.pattern() {
+            Pattern::Scattered => {
+                // a stride coprime with the length visits every cell once
+                let stride = (len * 5 / 8..len).find(|&s| gcd(s, len) == 1).unwrap_or(1);
+                let start = xorshift(self.frame as u32) as usize % len;
+                for i in 0..count {
+                    bump(&mut self.cells[(start + i * stride) % len]);
+                }
+            }
+            Pattern::Rows => {
+                let start = self.frame * count % len;
+                for i in 0..count {
+                    bump(&mut self.cells[(start + i) % len]);
+                }
+            }
+            Pattern::Columns => {
+                let start = self.frame * count % len;
+                for i in 0..count {
+                    let j = (start + i) % len;
+                    bump(&mut self.cells[(j % height) * width + j / height]);
+                }
+            }
+            Pattern::Rect => {
+                let scale = (self.ratio() as f32 / 100.0).sqrt();
+                let rect_width = ((width as f32 * scale).round() as usize).clamp(1, width);
+                let rect_height = ((count as f32 / rect_width as f32).round() as usize).clamp(1, height);
+                let x0 = self.frame % (width - rect_width + 1);
+                let y0 = self.frame % (height - rect_height + 1);
+                for y in y0..y0 + rect_height {
+                    for cell in &mut self.cells[y * width + x0..y * width + x0 + rect_width] {
+                        bump(cell);
+                    }
+                }
+                return rect_width * rect_height;
+            }
+        }
+        count
+    }
+}
+
+impl Default for DirtyApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for DirtyApp {
+    /// A press moves to the next ratio, then the next pattern; a double press skips the rest
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press => self.next_stage().then_some(SceneExit::Next),
+            ButtonEvent::DoublePress => Some(SceneExit::Next),
+            ButtonEvent::LongPress => Some(SceneExit::Quit),
+        }
+    }
+
+    fn mode(&self) -> Option<&'static str> {
+        STAGES.get(self.stage).copied()
+    }
+
+    /// Average cells changed and drawn per frame of the current stage
+    fn summary(&self) -> Option<CompactString> {
+        let frames = self.frames.checked_sub(1).filter(|&frames| frames > 0)?;
+        Some(format_compact!("{}/{} cells", self.changed / self.frames, self.drawn / frames))
+    }
+
+    fn update(&mut self, _elapsed: Duration) {
+        // until the first frame of a stage is drawn, the last frame is the previous stage's
+        if self.frames > 0 {
+            self.drawn += instrument::last_frame().cells;
+        }
+        self.frame += 1;
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ]).split(area);
+
+        self.render_header(layout[0], buf);
+        self.render_cells(layout[1], buf);
+        self.render_footer(layout[2], buf, fps);
+    }
+}
+
+impl DirtyApp {
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let title = format_compact!("Dirty Cells [{}]", STAGES[self.stage]);
+        render_header(area, buf, &title, CATPPUCCIN.peach);
+    }
+
+    fn render_cells(&mut self, area: Rect, buf: &mut Buffer) {
+        if area != self.area {
+            self.area = area;
+            self.cells = (0..area.area() as usize).map(|i| xorshift(i as u32) as u8).collect();
+        }
+
+        self.changed += self.mutate();
+        self.frames += 1;
+
+        let colors = colors();
+        for (i, &generation) in self.cells.iter().enumerate() {
+            let x = area.x + (i % area.width as usize) as u16;
+            let y = area.y + (i / area.width as usize) as u16;
+            // consecutive generations differ in both glyph and color
+            buf[(x, y)]
+                .set_char((b'a' + generation % 26) as char)
+                .set_fg(colors[generation as usize % colors.len()]);
+        }
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
+        let fps_area = area.inner(Margin::new(8, 0));
+        fps.render(fps_area, buf);
+
+        // right-aligned past the fps text, so that it doesn't restyle it
+        let draw = instrument::last_frame();
+        let text = format_compact!("{} cells drawn", draw.cells);
+        let width = (text.len() as u16).min(fps_area.width.saturating_sub(FpsWidget::TEXT_WIDTH + 1));
+        buf.set_stringn(fps_area.right() - width, fps_area.y, text.as_str(), usize::from(width), Style::default().fg(CATPPUCCIN.subtext0));
+    }
+}
+
+fn bump(generation: &mut u8) {
+    *generation = generation.wrapping_add(1);
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Cheap hash, so that patterns look random but repeat exactly across runs
+fn xorshift(seed: u32) -> u32 {
+    let mut x = seed.wrapping_mul(0x9E37_79B9) | 1;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    x
+}
+
+fn colors() -> [Color; 5] {
+    [
+        CATPPUCCIN.text,
+        CATPPUCCIN.blue,
+        CATPPUCCIN.green,
+        CATPPUCCIN.peach,
+        CATPPUCCIN.mauve,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use crate::input::ScriptedInput;
+    use crate::scene::SceneRunner;
+
+    /// Cells of the content area that changed between two consecutive frames of `stage`
+    fn changed_cells(stage: usize) -> usize {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut scene = DirtyApp::new();
+        scene.stage = stage;
+
+        let fps = FpsWidget::new();
+        terminal.draw(|frame| scene.render(frame, &fps)).unwrap();
+        let before = terminal.backend().buffer().clone();
+        scene.update(Duration::ZERO);
+        terminal.draw(|frame| scene.render(frame, &fps)).unwrap();
+        let after = terminal.backend().buffer();
+
+        (3..23).flat_map(|y| (0..53).map(move |x| (x, y)))
+            .filter(|&pos| before[pos] != after[pos])
+            .count()
+    }
+
+    #[test]
+    fn test_changes_the_ratio_of_cells() {
+        // 53 x 20 cells
+        for pattern in 0..3 {
+            let stage = pattern * RATIOS.len();
+            assert_eq!(changed_cells(stage), 0);
+            assert_eq!(changed_cells(stage + 1), 11);
+            assert_eq!(changed_cells(stage + 2), 106);
+            assert_eq!(changed_cells(stage + 5), 1060);
+        }
+
+        // 17 x 6 cells
+        assert_eq!(changed_cells(3 * RATIOS.len() + 2), 102);
+        assert_eq!(changed_cells(3 * RATIOS.len() + 5), 1060);
+    }
+
+    #[test]
+    fn test_press_cycles_every_stage_then_exits() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new();
+        for frame in 1..=STAGES.len() {
+            input = input.at(frame * 2, ButtonEvent::Press);
+        }
+
+        let mut scene = DirtyApp::new();
+        let exit = SceneRunner::new().run(&mut scene, &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Next);
+        assert_eq!(scene.mode(), Some("scattered 0%"));
+    }
+}
@@ -172,9 +172,9 @@ mod tests {
 
     #[test]
     fn test_from_str_multiple_chars() {
-        // Should only take first character
+        // Should fallback to space for more than 3 bytes
         let embedded = EmbeddedStr::from("hello");
-        assert_eq!(embedded.as_str(), "h");
+        assert_eq!(embedded.as_str(), " ");
         assert_eq!(embedded.len, 1);
     }
 
@@ -0,0 +1,820 @@
+//! Machine-readable records of a [`Report`], for collecting results off the serial log.
+//!
+//! Every record carries the run id, build profile and font, so that records
+//! from several boots and builds can be concatenated and compared. Frame
+//! records hold the time spent on every frame, microbenchmark records the
+//! time of every sample, both in nanoseconds.
+
+use std::fmt::Write as _;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+use compact_str::{format_compact, CompactString};
+use crate::instrument::DrawStats;
+use crate::phases::{FramePhases, Phase};
+use crate::platform;
+use crate::report::Report;
+use crate::scene::SceneRun;
+use crate::throughput::PixelStats;
+
+/// Output format of the [`Exporter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One JSON object per line
+    JsonLines,
+    /// Comma-separated values with a single header line; samples are `;`-separated
+    Csv,
+}
+
+/// Error returned when parsing an unknown [`Format`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownFormat(pub String);
+
+impl std::fmt::Display for UnknownFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown export format: {} (expected jsonl or csv)", self.0)
+    }
+}
+
+impl std::error::Error for UnknownFormat {}
+
+impl FromStr for Format {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jsonl" | "json" => Ok(Format::JsonLines),
+            "csv" => Ok(Format::Csv),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// What every record is tagged with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunInfo {
+    /// Random per boot, as 8 hex digits
+    pub run_id: CompactString,
+    /// `debug` or `release`
+    pub profile: &'static str,
+    /// Regular font of the backend
+    pub font: &'static str,
+}
+
+impl RunInfo {
+    pub fn new(font: &'static str) -> Self {
+        Self {
+            run_id: format_compact!("{:08x}", platform::run_id()),
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+            font,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "run_id,profile,font,kind,scene,mode,name,iterations,count,elapsed_ns,median_ns,\
+    render_ns,effects_ns,diff_ns,flush_ns,cells,runs,rows,draw_calls,flushes,backend_draw_ns,backend_flush_ns,\
+    pixels,draw_iter_calls,fill_solid_calls,fill_contiguous_calls,windows,bbox_area,spi_bytes,spi_transactions,spi_est_ns,\
+    samples_ns";
+
+/// Fields of frames records only, in column order
+const FRAME_FIELDS: [&str; 20] = [
+    "render_ns",
+    "effects_ns",
+    "diff_ns",
+    "flush_ns",
+    "cells",
+    "runs",
+    "rows",
+    "draw_calls",
+    "flushes",
+    "backend_draw_ns",
+    "backend_flush_ns",
+    "pixels",
+    "draw_iter_calls",
+    "fill_solid_calls",
+    "fill_contiguous_calls",
+    "windows",
+    "bbox_area",
+    "spi_bytes",
+    "spi_transactions",
+    "spi_est_ns",
+];
+
+/// Values of the [`FRAME_FIELDS`] of a run
+fn frame_values(run: &SceneRun) -> [u128; FRAME_FIELDS.len()] {
+    let (phases, draw, pixels) = (&run.phases, &run.draw, &run.pixels);
+    [
+        phases.render.as_nanos(),
+        phases.effects.as_nanos(),
+        phases.diff.as_nanos(),
+        phases.flush.as_nanos(),
+        draw.cells as u128,
+        draw.runs as u128,
+        draw.rows as u128,
+        draw.draw_calls as u128,
+        draw.flushes as u128,
+        draw.draw_time.as_nanos(),
+        draw.flush_time.as_nanos(),
+        pixels.pixels.into(),
+        pixels.draw_iter_calls.into(),
+        pixels.fill_solid_calls.into(),
+        pixels.fill_contiguous_calls.into(),
+        pixels.windows.into(),
+        pixels.bbox_area.into(),
+        pixels.spi_bytes.into(),
+        pixels.spi_transactions.into(),
+        pixels.spi_time.as_nanos(),
+    ]
+}
+
+/// Writes the records of reports in one [`Format`]
+#[derive(Debug, Clone)]
+pub struct Exporter {
+    format: Format,
+    run: RunInfo,
+    header_written: bool,
+}
+
+impl Exporter {
+    pub fn new(format: Format, run: RunInfo) -> Self {
+        Self { format, run, header_written: false }
+    }
+
+    pub fn run(&self) -> &RunInfo {
+        &self.run
+    }
+
+    /// Writes one record per stage and per microbenchmark of `report`
+    ///
+    /// CSV output starts with a header line, written before the first report only.
+    pub fn write_report(&mut self, report: &Report, out: &mut impl io::Write) -> io::Result<()> {
+        if self.format == Format::Csv && !self.header_written {
+            writeln!(out, "{CSV_HEADER}")?;
+            self.header_written = true;
+        }
+
+        for stage in &report.stages {
+            let mode = match stage.mode {
+                Some(mode) => CompactString::from(mode),
+                None => format_compact!("{}", stage.stage),
+            };
+            let record = Record {
+                kind: "frames",
+                scene: stage.scene,
+                mode: &mode,
+                name: "",
+                iterations: 1,
+                count: stage.run.frames,
+                elapsed: stage.run.elapsed,
+                frame: Some(frame_values(&stage.run)),
+                samples: &stage.frame_times,
+            };
+            writeln!(out, "{}", self.format_record(&record))?;
+        }
+
+        for bench in &report.microbenchmarks {
+            let record = Record {
+                kind: "microbench",
+                scene: bench.scene,
+                mode: bench.group,
+                name: bench.name,
+                iterations: bench.measurement.iterations,
+                count: bench.measurement.samples.len(),
+                elapsed: bench.measurement.total(),
+                frame: None,
+                samples: &bench.measurement.samples,
+            };
+            writeln!(out, "{}", self.format_record(&record))?;
+        }
+
+        Ok(())
+    }
+
+    fn format_record(&self, record: &Record) -> String {
+        match self.format {
+            Format::JsonLines => record.to_json(&self.run),
+            Format::Csv => record.to_csv(&self.run),
+        }
+    }
+}
+
+/// A row of the export, shared by frame and microbenchmark records
+struct Record<'a> {
+    kind: &'static str,
+    scene: &'a str,
+    /// Stage mode or microbenchmark group
+    mode: &'a str,
+    /// Microbenchmark name; empty for frames
+    name: &'a str,
+    /// Per sample
+    iterations: u32,
+    /// Frames, or samples of a microbenchmark
+    count: usize,
+    elapsed: Duration,
+    /// The [`FRAME_FIELDS`], summed over all frames; microbenchmarks have none
+    frame: Option<[u128; FRAME_FIELDS.len()]>,
+    samples: &'a [Duration],
+}
+
+impl Record<'_> {
+    /// Same as [`Measurement::median()`](crate::microbench::Measurement::median), but
+    /// for frame times too, which may be empty
+    fn median(&self) -> Duration {
+        let mut sorted = self.samples.to_vec();
+        sorted.sort_unstable();
+
+        let mid = sorted.len() / 2;
+        match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 0 => (sorted[mid - 1] + sorted[mid]) / 2,
+            _ => sorted[mid],
+        }
+    }
+
+    fn to_json(&self, run: &RunInfo) -> String {
+        let mut json = String::from("{");
+        for (key, value) in [
+            ("run_id", run.run_id.as_str()),
+            ("profile", run.profile),
+            ("font", run.font),
+            ("kind", self.kind),
+            ("scene", self.scene),
+            ("mode", self.mode),
+            ("name", self.name),
+        ] {
+            let _ = write!(json, "\"{key}\":");
+            push_json_str(&mut json, value);
+            json.push(',');
+        }
+
+        let _ = write!(
+            json,
+            "\"iterations\":{},\"count\":{},\"elapsed_ns\":{},\"median_ns\":{},",
+            self.iterations,
+            self.count,
+            self.elapsed.as_nanos(),
+            self.median().as_nanos(),
+        );
+        if let Some(values) = self.frame {
+            for (key, value) in FRAME_FIELDS.iter().zip(values) {
+                let _ = write!(json, "\"{key}\":{value},");
+            }
+        }
+        json.push_str("\"samples_ns\":[");
+        for (i, sample) in self.samples.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            let _ = write!(json, "{sep}{}", sample.as_nanos());
+        }
+        json.push_str("]}");
+        json
+    }
+
+    fn to_csv(&self, run: &RunInfo) -> String {
+        let mut csv = String::new();
+        for value in [
+            run.run_id.as_str(),
+            run.profile,
+            run.font,
+            self.kind,
+            self.scene,
+            self.mode,
+            self.name,
+        ] {
+            push_csv_field(&mut csv, value);
+            csv.push(',');
+        }
+
+        let _ = write!(
+            csv,
+            "{},{},{},{},",
+            self.iterations,
+            self.count,
+            self.elapsed.as_nanos(),
+            self.median().as_nanos(),
+        );
+        for idx in 0..FRAME_FIELDS.len() {
+            if let Some(values) = self.frame {
+                let _ = write!(csv, "{}", values[idx]);
+            }
+            csv.push(',');
+        }
+        for (i, sample) in self.samples.iter().enumerate() {
+            let sep = if i == 0 { "" } else { ";" };
+            let _ = write!(csv, "{sep}{}", sample.as_nanos());
+        }
+        csv
+    }
+}
+
+fn push_json_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn push_csv_field(out: &mut String, value: &str) {
+    if value.contains([',', '"', '\n']) {
+        out.push('"');
+        out.push_str(&value.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(value);
+    }
+}
+
+/// A record read back from an export by [`parse_records()`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportedRecord {
+    pub run_id: String,
+    pub profile: String,
+    pub font: String,
+    /// `frames` or `microbench`
+    pub kind: String,
+    pub scene: String,
+    pub mode: String,
+    pub name: String,
+    pub iterations: u32,
+    pub count: usize,
+    pub elapsed_ns: u64,
+    pub median_ns: u64,
+    /// Time spent in each phase over all frames; `None` for microbenchmarks
+    pub phases: Option<FramePhases>,
+    /// Backend work over all frames; `None` for microbenchmarks
+    pub draw: Option<DrawStats>,
+    /// Display writes over all frames; `None` for microbenchmarks and older exports
+    pub pixels: Option<PixelStats>,
+    pub samples_ns: Vec<u64>,
+}
+
+impl ExportedRecord {
+    pub fn is_microbench(&self) -> bool {
+        self.kind == "microbench"
+    }
+
+    /// Median time per iteration of a microbenchmark, in nanoseconds
+    pub fn ns_per_iter(&self) -> f64 {
+        self.median_ns as f64 / f64::from(self.iterations.max(1))
+    }
+
+    /// Average frame rate of a frames record
+    pub fn fps(&self) -> f64 {
+        self.count as f64 * 1e9 / self.elapsed_ns.max(1) as f64
+    }
+
+    /// Sets the field `key`; unknown keys are ignored, so that older tools read newer exports
+    fn set(&mut self, key: &str, value: Value) -> Result<(), &'static str> {
+        fn int<T: TryFrom<u64>>(value: Value) -> Result<T, &'static str> {
+            match value {
+                Value::Int(n) => T::try_from(n).map_err(|_| "number out of range"),
+                _ => Err("expected a number"),
+            }
+        }
+
+        let text = |value: Value| match value {
+            Value::Str(s) => Ok(s),
+            _ => Err("expected a string"),
+        };
+
+        if let Some(phase) = key.strip_suffix("_ns").and_then(Phase::from_name) {
+            let ns = int(value)?;
+            *self.phases.get_or_insert_default().get_mut(phase) = Duration::from_nanos(ns);
+            return Ok(());
+        }
+
+        match key {
+            "run_id" => self.run_id = text(value)?,
+            "profile" => self.profile = text(value)?,
+            "font" => self.font = text(value)?,
+            "kind" => self.kind = text(value)?,
+            "scene" => self.scene = text(value)?,
+            "mode" => self.mode = text(value)?,
+            "name" => self.name = text(value)?,
+            "iterations" => self.iterations = int(value)?,
+            "count" => self.count = int(value)?,
+            "elapsed_ns" => self.elapsed_ns = int(value)?,
+            "median_ns" => self.median_ns = int(value)?,
+            "samples_ns" => match value {
+                Value::Ints(samples) => self.samples_ns = samples,
+                _ => return Err("expected a list of numbers"),
+            },
+            "cells" => self.draw.get_or_insert_default().cells = int(value)?,
+            "runs" => self.draw.get_or_insert_default().runs = int(value)?,
+            "rows" => self.draw.get_or_insert_default().rows = int(value)?,
+            "draw_calls" => self.draw.get_or_insert_default().draw_calls = int(value)?,
+            "flushes" => self.draw.get_or_insert_default().flushes = int(value)?,
+            "backend_draw_ns" => self.draw.get_or_insert_default().draw_time = Duration::from_nanos(int(value)?),
+            "backend_flush_ns" => self.draw.get_or_insert_default().flush_time = Duration::from_nanos(int(value)?),
+            "pixels" => self.pixels.get_or_insert_default().pixels = int(value)?,
+            "draw_iter_calls" => self.pixels.get_or_insert_default().draw_iter_calls = int(value)?,
+            "fill_solid_calls" => self.pixels.get_or_insert_default().fill_solid_calls = int(value)?,
+            "fill_contiguous_calls" => self.pixels.get_or_insert_default().fill_contiguous_calls = int(value)?,
+            "windows" => self.pixels.get_or_insert_default().windows = int(value)?,
+            "bbox_area" => self.pixels.get_or_insert_default().bbox_area = int(value)?,
+            "spi_bytes" => self.pixels.get_or_insert_default().spi_bytes = int(value)?,
+            "spi_transactions" => self.pixels.get_or_insert_default().spi_transactions = int(value)?,
+            "spi_est_ns" => self.pixels.get_or_insert_default().spi_time = Duration::from_nanos(int(value)?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`parse_records()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Counts from 1
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Int(u64),
+    Ints(Vec<u64>),
+    /// A fraction, boolean, null or list of anything but numbers, which no field has
+    Other,
+}
+
+/// Reads the records of an export in either format, such as a captured serial log
+///
+/// Lines that are neither a JSON record (an object with a `run_id` and a `kind`
+/// of `frames` or `microbench`), a CSV header
+/// nor a CSV record following a header are skipped, so the report tables and log
+/// output may stay in the file. Records holding invalid values are errors.
+pub fn parse_records(text: &str) -> Result<Vec<ExportedRecord>, ParseError> {
+    let mut records = Vec::new();
+    let mut csv_header: Option<Vec<String>> = None;
+
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let error = |message| ParseError { line: idx + 1, message };
+
+        if line.starts_with('{') {
+            // other JSON in the log isn't a record
+            let Ok(fields) = parse_json_object(line) else { continue };
+            if !is_json_record(&fields) {
+                continue;
+            }
+
+            let mut record = ExportedRecord::default();
+            for (key, value) in fields {
+                record.set(&key, value).map_err(error)?;
+            }
+            records.push(record);
+        } else if line.starts_with("run_id,") {
+            csv_header = Some(split_csv(line).map_err(error)?);
+        } else if let Some(header) = &csv_header {
+            let Ok(fields) = split_csv(line) else { continue };
+            if fields.len() == header.len() {
+                records.push(parse_csv_record(header, fields).map_err(error)?);
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+/// Whether an object is a record: one with a `run_id` and a `kind` the exporter writes
+fn is_json_record(fields: &[(String, Value)]) -> bool {
+    let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|(_, value)| value);
+    matches!(field("run_id"), Some(Value::Str(_)))
+        && matches!(field("kind"), Some(Value::Str(kind)) if kind == "frames" || kind == "microbench")
+}
+
+fn parse_csv_record(header: &[String], fields: Vec<String>) -> Result<ExportedRecord, &'static str> {
+    let mut record = ExportedRecord::default();
+    for (key, field) in header.iter().zip(fields) {
+        let value = match key.as_str() {
+            "samples_ns" => Value::Ints(field.split(';')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().map_err(|_| "expected a number"))
+                .collect::<Result<_, _>>()?),
+            // frame fields are left empty for microbenchmarks
+            _ if FRAME_FIELDS.contains(&key.as_str()) && field.is_empty() => continue,
+            _ if key.ends_with("_ns") || FRAME_FIELDS.contains(&key.as_str()) || key == "iterations" || key == "count" => {
+                Value::Int(field.parse().map_err(|_| "expected a number")?)
+            }
+            _ => Value::Str(field),
+        };
+        record.set(key, value)?;
+    }
+    Ok(record)
+}
+
+fn split_csv(line: &str) -> Result<Vec<String>, &'static str> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("unterminated quote");
+    }
+    Ok(fields)
+}
+
+/// Parses a flat object like those written by [`Exporter`] into its keys and values
+fn parse_json_object(line: &str) -> Result<Vec<(String, Value)>, &'static str> {
+    let mut json = JsonCursor { rest: line };
+    let mut fields = Vec::new();
+
+    json.expect('{')?;
+    if !json.eat('}') {
+        loop {
+            let key = json.string()?;
+            json.expect(':')?;
+            fields.push((key, json.value()?));
+
+            if json.eat('}') {
+                break;
+            }
+            json.expect(',')?;
+        }
+    }
+
+    if !json.rest.trim().is_empty() {
+        return Err("trailing characters after the record");
+    }
+    Ok(fields)
+}
+
+struct JsonCursor<'a> {
+    rest: &'a str,
+}
+
+impl JsonCursor<'_> {
+    fn eat(&mut self, c: char) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), &'static str> {
+        if self.eat(c) { Ok(()) } else { Err("malformed JSON record") }
+    }
+
+    fn value(&mut self) -> Result<Value, &'static str> {
+        self.rest = self.rest.trim_start();
+        if self.rest.starts_with('"') {
+            return self.string().map(Value::Str);
+        }
+
+        if !self.eat('[') {
+            return self.scalar();
+        }
+
+        let mut list = Vec::new();
+        if !self.eat(']') {
+            loop {
+                list.push(self.value()?);
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+
+        let ints: Option<Vec<u64>> = list.into_iter()
+            .map(|value| match value {
+                Value::Int(n) => Some(n),
+                _ => None,
+            })
+            .collect();
+        Ok(ints.map_or(Value::Other, Value::Ints))
+    }
+
+    /// A number, `true`, `false` or `null`
+    fn scalar(&mut self) -> Result<Value, &'static str> {
+        let end = self.rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '+' | '-'))
+            .unwrap_or(self.rest.len());
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+
+        if token.is_empty() {
+            return Err("malformed JSON record");
+        }
+        Ok(token.parse().map_or(Value::Other, Value::Int))
+    }
+
+    fn string(&mut self) -> Result<String, &'static str> {
+        self.expect('"')?;
+
+        let mut out = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[idx + 1..];
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => out.push('\n'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        out.push(c.ok_or("invalid escape")?);
+                    }
+                    Some(c @ ('"' | '\\' | '/')) => out.push(c),
+                    _ => return Err("invalid escape"),
+                },
+                c => out.push(c),
+            }
+        }
+
+        Err("unterminated string")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::microbench::Measurement;
+    use crate::report::{BenchReport, StageReport};
+
+    fn run() -> RunInfo {
+        RunInfo { run_id: "0badf00d".into(), profile: "release", font: "mono_6x10" }
+    }
+
+    fn report() -> Report {
+        let ms = Duration::from_millis;
+        Report {
+            stages: vec![StageReport {
+                scene: "text",
+                stage: 2,
+                mode: Some("accent"),
+                run: SceneRun {
+                    frames: 3,
+                    elapsed: ms(60),
+                    phases: FramePhases { render: ms(30), effects: ms(10), diff: ms(5), flush: ms(15) },
+                    draw: DrawStats {
+                        cells: 900,
+                        runs: 60,
+                        rows: 40,
+                        draw_calls: 3,
+                        flushes: 3,
+                        draw_time: ms(12),
+                        flush_time: ms(3),
+                    },
+                    pixels: PixelStats {
+                        pixels: 21_600,
+                        draw_iter_calls: 900,
+                        fill_solid_calls: 0,
+                        fill_contiguous_calls: 0,
+                        windows: 21_600,
+                        bbox_area: 38_400,
+                        spi_bytes: 280_800,
+                        spi_transactions: 129_600,
+                        spi_time: Duration::from_micros(28_080),
+                    },
+                },
+                summary: None,
+                frame_times: vec![ms(10), ms(30), ms(20)],
+            }],
+            microbenchmarks: vec![BenchReport {
+                scene: "compute",
+                group: "u32",
+                name: "ADD",
+                measurement: Measurement::new(1000, vec![Duration::from_nanos(500), Duration::from_nanos(700)]),
+            }],
+        }
+    }
+
+    fn export(format: Format, reports: usize) -> String {
+        let mut exporter = Exporter::new(format, run());
+        let mut out = Vec::new();
+        for _ in 0..reports {
+            exporter.write_report(&report(), &mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let out = export(Format::JsonLines, 1);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines, [
+            r#"{"run_id":"0badf00d","profile":"release","font":"mono_6x10","kind":"frames","scene":"text","mode":"accent","name":"","iterations":1,"count":3,"elapsed_ns":60000000,"median_ns":20000000,"render_ns":30000000,"effects_ns":10000000,"diff_ns":5000000,"flush_ns":15000000,"cells":900,"runs":60,"rows":40,"draw_calls":3,"flushes":3,"backend_draw_ns":12000000,"backend_flush_ns":3000000,"pixels":21600,"draw_iter_calls":900,"fill_solid_calls":0,"fill_contiguous_calls":0,"windows":21600,"bbox_area":38400,"spi_bytes":280800,"spi_transactions":129600,"spi_est_ns":28080000,"samples_ns":[10000000,30000000,20000000]}"#,
+            r#"{"run_id":"0badf00d","profile":"release","font":"mono_6x10","kind":"microbench","scene":"compute","mode":"u32","name":"ADD","iterations":1000,"count":2,"elapsed_ns":1200,"median_ns":600,"samples_ns":[500,700]}"#,
+        ]);
+    }
+
+    #[test]
+    fn test_csv_header_is_written_once() {
+        let out = export(Format::Csv, 2);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "0badf00d,release,mono_6x10,frames,text,accent,,1,3,60000000,20000000,30000000,10000000,5000000,15000000,900,60,40,3,3,12000000,3000000,21600,900,0,0,21600,38400,280800,129600,28080000,10000000;30000000;20000000");
+        assert_eq!(lines[4], "0badf00d,release,mono_6x10,microbench,compute,u32,ADD,1000,2,1200,600,,,,,,,,,,,,,,,,,,,,,500;700");
+    }
+
+    #[test]
+    fn test_escaping() {
+        let mut json = String::new();
+        push_json_str(&mut json, "a\"b\\c\n");
+        assert_eq!(json, r#""a\"b\\c\n""#);
+
+        let mut csv = String::new();
+        push_csv_field(&mut csv, "x,\"y\"");
+        assert_eq!(csv, r#""x,""y""""#);
+    }
+
+    #[test]
+    fn test_parse_records_round_trip() {
+        for format in [Format::JsonLines, Format::Csv] {
+            let log = format!("I (123) boot: starting\n{}total 1 3\n", export(format, 1));
+            let records = parse_records(&log).unwrap();
+
+            assert_eq!(records.len(), 2, "{format:?}");
+            assert_eq!(records[0].mode, "accent");
+            assert_eq!(records[0].samples_ns, [10_000_000, 30_000_000, 20_000_000]);
+            assert_eq!(records[0].fps(), 50.0);
+            assert_eq!(records[0].phases.map(|p| p.flush), Some(Duration::from_millis(15)));
+            assert_eq!(records[0].draw.map(|d| (d.cells, d.flush_time)), Some((900, Duration::from_millis(3))));
+            assert_eq!(records[1].phases, None);
+            // every frame field is read back into its own stat
+            assert_eq!(records[0].draw, Some(report().stages[0].run.draw));
+            assert_eq!(records[0].pixels, Some(report().stages[0].run.pixels));
+            assert_eq!(records[0].pixels.map(|p| (p.windows, p.spi_time)), Some((21_600, Duration::from_micros(28_080))));
+            assert_eq!(records[1].draw, None);
+            assert_eq!(records[1].pixels, None);
+            assert!(records[1].is_microbench());
+            assert_eq!((records[1].run_id.as_str(), records[1].name.as_str()), ("0badf00d", "ADD"));
+            assert_eq!(records[1].ns_per_iter(), 0.6);
+        }
+    }
+
+    #[test]
+    fn test_parse_records_errors() {
+        let err = parse_records("log\n{\"run_id\":\"1\",\"kind\":\"frames\",\"count\":\"three\"}").unwrap_err();
+        assert_eq!(err, ParseError { line: 2, message: "expected a number" });
+
+        let err = parse_records(r#"{"run_id":"1","kind":"microbench","count":1.5}"#).unwrap_err();
+        assert_eq!(err, ParseError { line: 1, message: "expected a number" });
+
+        let records = parse_records(r#"{"run_id":"1","kind":"microbench","name":"a\"b\u00e9","future":[1,2],"count":3}"#).unwrap();
+        assert_eq!((records[0].name.as_str(), records[0].count), ("a\"bé", 3));
+    }
+
+    #[test]
+    fn test_parse_records_skips_other_json() {
+        let log = [
+            r#"{"level":"info","msg":"wifi up"}"#,
+            r#"{"task":"main","stack": {"free":2048}}"#,
+            r#"{not json"#,
+            // shares keys with the export, but isn't a record
+            r#"{"name":"wifi"}"#,
+            r#"{"msg":"flushed","count":"3 frames"}"#,
+            r#"{"run_id":"1","kind":"log","count":"three"}"#,
+            r#"{"run_id":"1","kind":"frames","scene":"text","count":3,"ratio":0.5,"late":true,"parent":null,"tags":["a"]}"#,
+        ].join("\n");
+
+        let records = parse_records(&log).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].scene.as_str(), records[0].count), ("text", 3));
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("jsonl".parse(), Ok(Format::JsonLines));
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -1,17 +1,40 @@
+use std::str::FromStr;
 use std::time::{Duration, Instant};
-use compact_str::format_compact;
+use compact_str::{format_compact, CompactString};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::Style,
-    widgets::{Paragraph, Widget},
+    widgets::Widget,
 };
 
+/// Number of recent frame durations kept by [`Fps`]
+pub const FRAME_WINDOW: usize = 256;
+
+/// Distribution of the frame durations in the window of an [`Fps`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Frames in the window
+    pub frames: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
 #[derive(Debug)]
 pub struct Fps {
     frame_count: usize,
     last_instant: Instant,
     fps: Option<f32>,
+    last_frame: Option<Instant>,
+    /// Ring of the most recent frame durations, in microseconds
+    frame_us: [u32; FRAME_WINDOW],
+    /// Frames recorded so far; the next one goes to `recorded % FRAME_WINDOW`
+    recorded: usize,
+    /// Refreshed together with `fps`
+    stats: Option<FrameStats>,
 }
 
 impl Default for Fps {
@@ -20,22 +43,135 @@ impl Default for Fps {
             frame_count: 0,
             last_instant: Instant::now(),
             fps: None,
+            last_frame: None,
+            frame_us: [0; FRAME_WINDOW],
+            recorded: 0,
+            stats: None,
         }
     }
 }
 
 impl Fps {
     pub fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            self.record(now - last_frame);
+        }
+        self.last_frame = Some(now);
+
         self.frame_count += 1;
         let elapsed = self.last_instant.elapsed();
         // update the fps every second, but only if we've rendered at least 2 frames (to avoid
         // noise in the fps calculation)
         if elapsed > Duration::from_millis(250) && self.frame_count > 2 {
             self.fps = Some(self.frame_count as f32 / elapsed.as_secs_f32());
+            self.stats = self.frame_stats();
             self.frame_count = 0;
             self.last_instant = Instant::now();
         }
     }
+
+    /// Adds a frame duration to the window, evicting the oldest once it is full
+    pub fn record(&mut self, frame: Duration) {
+        let us = frame.as_micros().min(u32::MAX as u128) as u32;
+        self.frame_us[self.recorded % FRAME_WINDOW] = us;
+        self.recorded += 1;
+    }
+
+    /// Average frame rate, refreshed every 250 ms
+    pub fn fps(&self) -> Option<f32> {
+        self.fps
+    }
+
+    /// Frame statistics as of the last fps refresh
+    pub fn stats(&self) -> Option<FrameStats> {
+        self.stats
+    }
+
+    /// Durations in the window, oldest first
+    pub fn frame_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        let len = self.recorded.min(FRAME_WINDOW);
+        let start = self.recorded - len;
+        (start..self.recorded).map(|i| Duration::from_micros(self.frame_us[i % FRAME_WINDOW].into()))
+    }
+
+    /// Current statistics of the window, computed without allocating
+    pub fn frame_stats(&self) -> Option<FrameStats> {
+        let len = self.recorded.min(FRAME_WINDOW);
+        if len == 0 {
+            return None;
+        }
+
+        let mut sorted = self.frame_us;
+        let sorted = &mut sorted[..len];
+        sorted.sort_unstable();
+
+        // nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * len).div_ceil(100).max(1);
+            Duration::from_micros(sorted[rank - 1].into())
+        };
+
+        Some(FrameStats {
+            frames: len,
+            min: Duration::from_micros(sorted[0].into()),
+            max: Duration::from_micros(sorted[len - 1].into()),
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+        })
+    }
+
+    /// Counts the frames of the window in `N` buckets of `bucket` width each;
+    /// the last bucket also holds every longer frame
+    pub fn histogram<const N: usize>(&self, bucket: Duration) -> [usize; N] {
+        let mut counts = [0; N];
+        let width = bucket.as_micros().max(1);
+        for frame in self.frame_times() {
+            let idx = (frame.as_micros() / width) as usize;
+            if let Some(count) = counts.get_mut(idx.min(N.saturating_sub(1))) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// What the [`FpsWidget`] shows
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FpsMode {
+    /// Average frames per second
+    #[default]
+    Fps,
+    /// Average milliseconds per frame
+    FrameTime,
+    /// 99th percentile frame time in milliseconds
+    P99,
+}
+
+/// Error returned when parsing an unknown [`FpsMode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownFpsMode(pub String);
+
+impl std::fmt::Display for UnknownFpsMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown fps mode: {} (expected fps, ms or p99)", self.0)
+    }
+}
+
+impl std::error::Error for UnknownFpsMode {}
+
+impl FromStr for FpsMode {
+    type Err = UnknownFpsMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fps" => Ok(FpsMode::Fps),
+            "ms" => Ok(FpsMode::FrameTime),
+            "p99" => Ok(FpsMode::P99),
+            _ => Err(UnknownFpsMode(s.to_string())),
+        }
+    }
 }
 
 #[derive(Default, Debug)]
@@ -43,9 +179,13 @@ pub struct FpsWidget {
     pub fps: Fps,
     pub show_label: bool,
     pub style: Style,
+    pub mode: FpsMode,
 }
 
 impl FpsWidget {
+    /// Columns the text takes at most, with the label, for frames under a second
+    pub const TEXT_WIDTH: u16 = 15;
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -59,17 +199,108 @@ impl FpsWidget {
         self.style = style.into();
         self
     }
+
+    pub fn with_mode(mut self, mode: FpsMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The text shown in the current mode; short enough to stay inline in a `CompactString`
+    fn text(&self) -> Option<CompactString> {
+        let fps = self.fps.fps()?;
+        let (value, label) = match self.mode {
+            FpsMode::Fps => (fps, "fps"),
+            FpsMode::FrameTime => (1000.0 / fps.max(f32::EPSILON), "ms/frame"),
+            FpsMode::P99 => (self.fps.stats()?.p99.as_secs_f32() * 1000.0, "ms p99"),
+        };
+
+        Some(match (self.show_label, self.mode) {
+            (true, FpsMode::Fps) => format_compact!("{value:.1} {label}"),
+            (true, _) => format_compact!("{value:.2} {label}"),
+            (false, FpsMode::Fps) => format_compact!("{value:.1}"),
+            (false, _) => format_compact!("{value:.2}"),
+        })
+    }
 }
 
 impl Widget for &FpsWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if let Some(fps) = self.fps.fps {
-            let text = if self.show_label {
-                format_compact!("{fps:.1} fps")
-            } else {
-                format_compact!("{fps:.1}")
-            };
-            Paragraph::new(text.as_str()).style(self.style).render(area, buf);
+        // written straight to the buffer: a Paragraph would allocate every frame
+        if let Some(text) = self.text().filter(|_| !area.is_empty()) {
+            buf.set_stringn(area.x, area.y, text.as_str(), area.width as usize, self.style);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_percentiles_of_the_window() {
+        let mut fps = Fps::default();
+        assert_eq!(fps.frame_stats(), None);
+
+        // 98 smooth frames and two stutters
+        for i in 0..100 {
+            fps.record(if i % 50 == 7 { ms(80) } else { ms(10 + i % 3) });
+        }
+
+        let stats = fps.frame_stats().unwrap();
+        assert_eq!(stats.frames, 100);
+        assert_eq!((stats.min, stats.max), (ms(10), ms(80)));
+        assert_eq!(stats.p50, ms(11));
+        assert_eq!(stats.p95, ms(12));
+        assert_eq!(stats.p99, ms(80));
+    }
+
+    #[test]
+    fn test_window_keeps_most_recent_frames() {
+        let mut fps = Fps::default();
+        for i in 0..FRAME_WINDOW as u64 + 10 {
+            fps.record(ms(i));
+        }
+
+        assert_eq!(fps.frame_times().count(), FRAME_WINDOW);
+        assert_eq!(fps.frame_times().next(), Some(ms(10)));
+        assert_eq!(fps.frame_stats().unwrap().min, ms(10));
+    }
+
+    #[test]
+    fn test_histogram_clamps_long_frames() {
+        let mut fps = Fps::default();
+        for frame in [1, 4, 5, 9, 12, 500] {
+            fps.record(ms(frame));
+        }
+
+        assert_eq!(fps.histogram::<3>(ms(5)), [2, 2, 2]);
+    }
+
+    #[test]
+    fn test_widget_modes() {
+        let mut widget = FpsWidget::new().with_label(true);
+        widget.fps.fps = Some(50.0);
+        widget.fps.record(ms(30));
+        widget.fps.stats = widget.fps.frame_stats();
+
+        let text = |widget: &FpsWidget| widget.text().unwrap();
+        assert_eq!(text(&widget), "50.0 fps");
+        widget.mode = FpsMode::FrameTime;
+        assert_eq!(text(&widget), "20.00 ms/frame");
+        widget.mode = FpsMode::P99;
+        assert_eq!(text(&widget), "30.00 ms p99");
+
+        widget.mode = FpsMode::FrameTime;
+        widget.fps.fps = Some(1.001);
+        assert_eq!(text(&widget).len(), FpsWidget::TEXT_WIDTH as usize);
+        widget.mode = FpsMode::P99;
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 1));
+        (&widget).render(buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["30.00 ms"]));
+    }
+}
@@ -0,0 +1,135 @@
+use std::convert::Infallible;
+use std::io::{self, Write};
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use embedded_graphics::prelude::{Dimensions, DrawTarget, OriginDimensions, Pixel, Point, RgbColor, Size};
+use embedded_graphics::primitives::Rectangle;
+
+/// In-memory RGB565 display, standing in for the ST7789 panel on the host
+///
+/// Pixels outside the framebuffer are silently discarded, matching how the
+/// panel clips out-of-bounds writes.
+#[derive(Debug, Clone)]
+pub struct Framebuffer {
+    size: Size,
+    pixels: Vec<Rgb565>,
+}
+
+impl Framebuffer {
+    /// Creates a black framebuffer of the given size
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![Rgb565::BLACK; (size.width * size.height) as usize],
+        }
+    }
+
+    /// Row-major pixel data
+    pub fn pixels(&self) -> &[Rgb565] {
+        &self.pixels
+    }
+
+    /// Returns the pixel at `point`, or `None` if it lies outside the framebuffer
+    pub fn pixel(&self, point: Point) -> Option<Rgb565> {
+        self.index_of(point).map(|idx| self.pixels[idx])
+    }
+
+    /// Writes the framebuffer as a binary PPM (P6) image
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.size.width, self.size.height)?;
+        let rgb: Vec<u8> = self.pixels.iter()
+            .map(|&c| Rgb888::from(c))
+            .flat_map(|c| [c.r(), c.g(), c.b()])
+            .collect();
+
+        out.write_all(&rgb)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let (x, y) = (point.x, point.y);
+        let in_bounds = x >= 0 && y >= 0
+            && (x as u32) < self.size.width
+            && (y as u32) < self.size.height;
+
+        in_bounds.then(|| y as usize * self.size.width as usize + x as usize)
+    }
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(idx) = self.index_of(point) {
+                self.pixels[idx] = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let width = self.size.width as usize;
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * width;
+            let (start, end) = (row + area.top_left.x as usize, row + bottom_right.x as usize);
+            self.pixels[start..=end].fill(color);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::RgbColor;
+
+    #[test]
+    fn test_draw_clips_out_of_bounds_pixels() {
+        let mut fb = Framebuffer::new(Size::new(4, 3));
+        fb.draw_iter([
+            Pixel(Point::new(1, 2), Rgb565::RED),
+            Pixel(Point::new(4, 0), Rgb565::GREEN),
+            Pixel(Point::new(-1, 0), Rgb565::GREEN),
+        ]).unwrap();
+
+        assert_eq!(fb.pixel(Point::new(1, 2)), Some(Rgb565::RED));
+        assert_eq!(fb.pixel(Point::new(4, 0)), None);
+        assert_eq!(fb.pixels().iter().filter(|&&c| c == Rgb565::GREEN).count(), 0);
+    }
+
+    #[test]
+    fn test_fill_solid_clipped_to_bounds() {
+        let mut fb = Framebuffer::new(Size::new(4, 3));
+        fb.fill_solid(&Rectangle::new(Point::new(2, 1), Size::new(10, 10)), Rgb565::BLUE)
+            .unwrap();
+
+        let blue = fb.pixels().iter().filter(|&&c| c == Rgb565::BLUE).count();
+        assert_eq!(blue, 4);
+        assert_eq!(fb.pixel(Point::new(3, 2)), Some(Rgb565::BLUE));
+        assert_eq!(fb.pixel(Point::new(1, 1)), Some(Rgb565::BLACK));
+    }
+
+    #[test]
+    fn test_write_ppm_header() {
+        let fb = Framebuffer::new(Size::new(2, 1));
+        let mut out = Vec::new();
+        fb.write_ppm(&mut out).unwrap();
+
+        assert!(out.starts_with(b"P6\n2 1\n255\n"));
+        assert_eq!(out.len(), b"P6\n2 1\n255\n".len() + 6);
+    }
+}
@@ -0,0 +1,345 @@
+use ratatui::Frame;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    symbols::{self, Marker},
+    text::Line,
+    widgets::{
+        Axis, BarChart, Block, Borders, Chart, Dataset, GraphType, LineGauge, List, ListItem, ListState,
+        Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline, StatefulWidget, Table,
+        TableState, Tabs, Widget, Wrap,
+    },
+};
+use compact_str::format_compact;
+use std::time::Duration;
+use crate::catpuccin::CATPPUCCIN;
+use crate::corpus::{self, Length, Words};
+use crate::fps::FpsWidget;
+use crate::header::render_header;
+use crate::input::ButtonEvent;
+use crate::scene::{Scene, SceneExit};
+
+/// Labels of the stages, one widget each, in the order a press cycles through them
+const STAGES: [&str; 9] = [
+    "list", "list scroll", "table", "sparkline", "barchart", "chart", "tabs", "scrollbar", "line gauge",
+];
+
+/// Items of the lists and rows of the table
+const TASKS: [&str; 20] = [
+    "wifi", "display", "button", "battery", "ntp", "mqtt", "ota", "logger", "httpd", "sensor",
+    "watchdog", "timer", "uart", "ble", "storage", "audio", "led", "touch", "imu", "shell",
+];
+
+/// Samples kept for the sparklines and chart, one per column of the panel
+const HISTORY: usize = 53;
+
+/// Gallery of ratatui's built-in widgets, each animated on its own for a stage
+///
+/// The average frame rate of every stage tells which widgets are affordable
+/// in the firmware UIs.
+#[derive(Debug)]
+pub struct GalleryApp {
+    stage: usize,
+    tick: usize,
+    /// Rolling samples of three signals, newest last
+    samples: [Vec<u64>; 3],
+    /// Two waveforms for the chart, rebuilt in place every frame
+    points: [Vec<(f64, f64)>; 2],
+    text: String,
+}
+
+impl GalleryApp {
+    pub fn new() -> Self {
+        Self {
+            stage: 0,
+            tick: 0,
+            samples: std::array::from_fn(|_| Vec::with_capacity(HISTORY)),
+            points: std::array::from_fn(|_| Vec::with_capacity(HISTORY * 2)),
+            text: Words::new(corpus::ENGLISH, Length::Chars(2048)).collect(),
+        }
+    }
+
+    /// Deterministic load figure of task `i`, between 0 and 99
+    fn load(&self, i: usize) -> u64 {
+        let phase = (self.tick + i * 37) as f32 / (8.0 + i as f32);
+        ((phase.sin() * 0.5 + 0.5) * 99.0) as u64
+    }
+}
+
+impl Default for GalleryApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for GalleryApp {
+    /// A press moves to the next widget, a double press skips the rest
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press => {
+                self.stage = (self.stage + 1) % STAGES.len();
+                (self.stage == 0).then_some(SceneExit::Next)
+            }
+            ButtonEvent::DoublePress => Some(SceneExit::Next),
+            ButtonEvent::LongPress => Some(SceneExit::Quit),
+        }
+    }
+
+    fn mode(&self) -> Option<&'static str> {
+        STAGES.get(self.stage).copied()
+    }
+
+    fn update(&mut self, _elapsed: Duration) {
+        self.tick += 1;
+
+        for (i, samples) in self.samples.iter_mut().enumerate() {
+            if samples.len() == HISTORY {
+                samples.remove(0);
+            }
+            let t = self.tick as f32 / (3.0 + i as f32 * 2.0);
+            samples.push(((t.sin() + (t * 2.7).cos() * 0.5 + 1.5) * 30.0) as u64);
+        }
+
+        for (i, points) in self.points.iter_mut().enumerate() {
+            points.clear();
+            points.extend((0..HISTORY * 2).map(|x| {
+                let t = (x + self.tick) as f64 / 8.0;
+                let y = if i == 0 { t.sin() } else { (t * 0.5).cos() * 0.6 };
+                (x as f64, y)
+            }));
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ]).split(area);
+
+        self.render_header(layout[0], buf);
+        let area = layout[1].inner(Margin::new(1, 0));
+        match STAGES[self.stage] {
+            "list" => self.render_list(area, buf),
+            "list scroll" => self.render_list_scroll(area, buf),
+            "table" => self.render_table(area, buf),
+            "sparkline" => self.render_sparklines(area, buf),
+            "barchart" => self.render_barchart(area, buf),
+            "chart" => self.render_chart(area, buf),
+            "tabs" => self.render_tabs(area, buf),
+            "scrollbar" => self.render_scrollbar(area, buf),
+            _ => self.render_line_gauges(area, buf),
+        }
+        self.render_footer(layout[2], buf, fps);
+    }
+}
+
+impl GalleryApp {
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let title = format_compact!("Widget Gallery [{} {}]", self.stage + 1, STAGES[self.stage]);
+        render_header(area, buf, &title, CATPPUCCIN.sapphire);
+    }
+
+    /// Every item changes its figure each frame
+    fn render_list(&self, area: Rect, buf: &mut Buffer) {
+        let items = TASKS.iter().enumerate().map(|(i, task)| {
+            let load = self.load(i);
+            let color = if load > 80 { CATPPUCCIN.red } else { CATPPUCCIN.text };
+            ListItem::new(format!("{task:<10} {load:>3}% {:<20}", "|".repeat(load as usize / 5)))
+                .style(Style::default().fg(color))
+        });
+
+        Widget::render(List::new(items), area, buf);
+    }
+
+    /// Static items, with the selection moving down and scrolling the list
+    fn render_list_scroll(&self, area: Rect, buf: &mut Buffer) {
+        let items = (0..TASKS.len() * 5).map(|i| {
+            ListItem::new(format!("{i:>3} {}", TASKS[i % TASKS.len()]))
+        });
+        let list = List::new(items)
+            .style(Style::default().fg(CATPPUCCIN.text))
+            .highlight_symbol("> ")
+            .highlight_style(Style::default().bg(CATPPUCCIN.surface0).fg(CATPPUCCIN.yellow));
+
+        let mut state = ListState::default().with_selected(Some(self.tick / 2 % (TASKS.len() * 5)));
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+
+    fn render_table(&self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(["task", "cpu", "mem", "state"])
+            .style(Style::default().fg(CATPPUCCIN.peach).add_modifier(Modifier::BOLD));
+        let rows = TASKS.iter().enumerate().map(|(i, task)| {
+            let load = self.load(i);
+            let state = if load > 50 { "run" } else { "wait" };
+            Row::new([
+                task.to_string(),
+                format!("{load}%"),
+                format!("{}k", 4 + self.load(i + 7) / 3),
+                state.to_string(),
+            ])
+        });
+
+        let table = Table::new(rows, [Constraint::Length(10), Constraint::Length(5), Constraint::Length(5), Constraint::Min(5)])
+            .header(header)
+            .style(Style::default().fg(CATPPUCCIN.text))
+            .row_highlight_style(Style::default().bg(CATPPUCCIN.surface0));
+
+        let mut state = TableState::default().with_selected(Some(self.tick / 4 % TASKS.len()));
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
+
+    fn render_sparklines(&self, area: Rect, buf: &mut Buffer) {
+        let colors = [CATPPUCCIN.green, CATPPUCCIN.blue, CATPPUCCIN.mauve];
+        let areas = Layout::vertical([Constraint::Ratio(1, 3); 3]).split(area);
+        for ((samples, color), area) in self.samples.iter().zip(colors).zip(areas.iter()) {
+            Sparkline::default()
+                .block(Block::new().borders(Borders::TOP).title(format!("max {}", samples.iter().max().unwrap_or(&0))))
+                .data(samples)
+                .style(Style::default().fg(color))
+                .render(*area, buf);
+        }
+    }
+
+    fn render_barchart(&self, area: Rect, buf: &mut Buffer) {
+        let bars: Vec<(&str, u64)> = TASKS[..8].iter().enumerate()
+            .map(|(i, task)| (*task, self.load(i)))
+            .collect();
+
+        BarChart::default()
+            .data(&bars)
+            .bar_width(5)
+            .bar_gap(1)
+            .max(100)
+            .bar_style(Style::default().fg(CATPPUCCIN.teal))
+            .value_style(Style::default().fg(CATPPUCCIN.base).bg(CATPPUCCIN.teal))
+            .label_style(Style::default().fg(CATPPUCCIN.text))
+            .render(area, buf);
+    }
+
+    fn render_chart(&self, area: Rect, buf: &mut Buffer) {
+        let datasets = vec![
+            Dataset::default()
+                .name("sin")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(CATPPUCCIN.green))
+                .data(&self.points[0]),
+            Dataset::default()
+                .name("cos")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(CATPPUCCIN.peach))
+                .data(&self.points[1]),
+        ];
+
+        let axis_style = Style::default().fg(CATPPUCCIN.subtext0);
+        Chart::new(datasets)
+            .x_axis(Axis::default().bounds([0.0, (HISTORY * 2) as f64]).style(axis_style).labels(["0", "106"]))
+            .y_axis(Axis::default().bounds([-1.0, 1.0]).style(axis_style).labels(["-1", "0", "1"]))
+            .render(area, buf);
+    }
+
+    /// One tab per corpus text, switching every 30 frames
+    fn render_tabs(&self, area: Rect, buf: &mut Buffer) {
+        let [tabs_area, body_area] = Layout::vertical([Constraint::Length(2), Constraint::Min(1)]).areas(area);
+        let selected = self.tick / 30 % corpus::ALL.len();
+
+        Tabs::new(corpus::ALL.map(|text| text.name))
+            .select(selected)
+            .style(Style::default().fg(CATPPUCCIN.subtext0))
+            .highlight_style(Style::default().fg(CATPPUCCIN.yellow).add_modifier(Modifier::REVERSED))
+            .divider(symbols::DOT)
+            .block(Block::new().borders(Borders::BOTTOM))
+            .render(tabs_area, buf);
+
+        Paragraph::new(corpus::ALL[selected].text)
+            .style(Style::default().fg(CATPPUCCIN.text))
+            .wrap(Wrap { trim: true })
+            .render(body_area, buf);
+    }
+
+    /// Text scrolling by a line every few frames, with both scrollbars
+    fn render_scrollbar(&self, area: Rect, buf: &mut Buffer) {
+        let lines = self.text.len() / area.width.max(1) as usize;
+        let position = self.tick / 3 % lines.max(1);
+
+        Paragraph::new(self.text.as_str())
+            .style(Style::default().fg(CATPPUCCIN.text))
+            .wrap(Wrap { trim: true })
+            .scroll((position as u16, 0))
+            .render(area.inner(Margin::new(1, 1)), buf);
+
+        let mut state = ScrollbarState::new(lines).position(position);
+        StatefulWidget::render(Scrollbar::new(ScrollbarOrientation::VerticalRight), area, buf, &mut state);
+        let mut state = ScrollbarState::new(area.width as usize * 2).position(self.tick % (area.width as usize * 2));
+        StatefulWidget::render(Scrollbar::new(ScrollbarOrientation::HorizontalBottom), area, buf, &mut state);
+    }
+
+    fn render_line_gauges(&self, area: Rect, buf: &mut Buffer) {
+        let symbols = [symbols::line::HORIZONTAL, symbols::line::THICK_HORIZONTAL, symbols::line::DOUBLE_HORIZONTAL];
+        let colors: [Color; 3] = [CATPPUCCIN.green, CATPPUCCIN.yellow, CATPPUCCIN.red];
+        let rows = Layout::vertical([Constraint::Length(2); 9]).split(area);
+
+        for (i, row) in rows.iter().enumerate() {
+            let ratio = self.load(i) as f64 / 100.0;
+            LineGauge::default()
+                .label(Line::from(format!("{:<8}", TASKS[i])))
+                .ratio(ratio)
+                .filled_symbol(symbols[i % symbols.len()])
+                .filled_style(Style::default().fg(colors[(ratio * 3.0) as usize % 3]))
+                .unfilled_style(Style::default().fg(CATPPUCCIN.surface1))
+                .render(*row, buf);
+        }
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
+        fps.render(area.inner(Margin::new(8, 0)), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use crate::input::ScriptedInput;
+    use crate::scene::SceneRunner;
+
+    #[test]
+    fn test_every_widget_renders() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut scene = GalleryApp::new();
+        let fps = FpsWidget::new();
+
+        for stage in STAGES {
+            assert_eq!(scene.mode(), Some(stage));
+            for _ in 0..3 {
+                scene.update(Duration::ZERO);
+                terminal.draw(|frame| scene.render(frame, &fps)).unwrap();
+            }
+
+            let buf = terminal.backend().buffer();
+            let content = (4..22).flat_map(|y| (0..53).map(move |x| (x, y)));
+            assert!(content.filter(|&pos| buf[pos].symbol() != " ").count() > 20, "{stage}");
+            scene.handle_input(ButtonEvent::Press);
+        }
+    }
+
+    #[test]
+    fn test_press_cycles_widgets_then_exits() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new();
+        for frame in 1..=STAGES.len() {
+            input = input.at(frame, ButtonEvent::Press);
+        }
+
+        let exit = SceneRunner::new().run(&mut GalleryApp::new(), &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Next);
+        assert!(input.is_exhausted());
+    }
+}
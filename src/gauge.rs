@@ -1,7 +1,4 @@
-use esp_idf_svc::hal::delay;
-use esp_idf_svc::hal::gpio::{Gpio0, Input, PinDriver};
-use esp_idf_svc::hal::task::notification::Notification;
-use ratatui::prelude::{Backend, Color, Terminal};
+use ratatui::prelude::{Color, Frame};
 use ratatui::widgets::BorderType;
 use ratatui::{
     buffer::Buffer,
@@ -10,56 +7,41 @@ use ratatui::{
     text::Line,
     widgets::{Block, Gauge, Padding, Widget},
 };
-use std::marker::PhantomData;
+use std::time::Duration;
 use crate::fps::FpsWidget;
-use crate::catpuccin::CATPPUCCIN;
+use crate::scene::Scene;
 
 #[derive(Debug)]
-pub struct GaugeApp<B: Backend> {
+pub struct GaugeApp {
     progress1: f64,
     progress2: f64,
-    fps_widget: FpsWidget,
-    _marker: PhantomData<B>,
 }
 
-impl<B: Backend> GaugeApp<B> {
+impl GaugeApp {
     pub fn new() -> Self {
         Self {
             progress1: 20.0,
             progress2: 20.0,
-            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
-            _marker: PhantomData,
-        }
-    }
-    pub fn run(
-        mut self,
-        terminal: &mut Terminal<B>,
-        notification: &mut Notification,
-        button: &mut PinDriver<Gpio0, Input>,
-    ) -> std::io::Result<()> {
-        button.enable_interrupt().unwrap();
-        loop {
-            if notification.wait(delay::NON_BLOCK).is_some() {
-                return Ok(());
-            }
-            self.fps_widget.fps.tick();
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))
-                .unwrap();
-            self.progress1 = (self.progress1 + 0.1).clamp(0.0, 100.0);
-            self.progress2 = (self.progress2 + 0.1).clamp(0.0, 100.0);
         }
     }
 }
 
-impl<B: Backend> Default for GaugeApp<B> {
+impl Default for GaugeApp {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<B: Backend> Widget for &GaugeApp<B> {
+impl Scene for GaugeApp {
+    fn update(&mut self, _elapsed: Duration) {
+        self.progress1 = (self.progress1 + 0.1).clamp(0.0, 100.0);
+        self.progress2 = (self.progress2 + 0.1).clamp(0.0, 100.0);
+    }
+
     #[allow(clippy::similar_names)]
-    fn render(self, area: Rect, buf: &mut Buffer) {
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
         use Constraint::{Length, Min, Ratio};
         let layout = Layout::vertical([Min(0), Length(1)]);
         let [gauge_area, footer_area] = layout.areas(area);
@@ -67,7 +49,7 @@ impl<B: Backend> Widget for &GaugeApp<B> {
         let layout = Layout::vertical([Ratio(1, 2); 2]);
         let [gauge1_area, gauge2_area] = layout.areas(gauge_area);
 
-        self.render_footer(footer_area, buf);
+        self.render_footer(footer_area, buf, fps);
 
         self.render_gauge1(gauge1_area, buf);
         self.render_gauge2(gauge2_area, buf);
@@ -75,12 +57,12 @@ impl<B: Backend> Widget for &GaugeApp<B> {
 }
 
 
-impl<B: Backend> GaugeApp<B> {
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+impl GaugeApp {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
         use ratatui::layout::Margin;
         
         let fps_area = area.inner(Margin::new(8, 0));
-        self.fps_widget.render(fps_area, buf);
+        fps.render(fps_area, buf);
     }
 
     fn render_gauge1(&self, area: Rect, buf: &mut Buffer) {
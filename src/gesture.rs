@@ -0,0 +1,263 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use crate::input::ButtonEvent;
+
+/// Timing thresholds for [`GestureRecognizer`]
+#[derive(Debug, Clone, Copy)]
+pub struct GestureConfig {
+    /// Edges closer than this to the previous accepted edge are treated as contact bounce,
+    /// unless the button is still in their state once it has passed
+    pub debounce: Duration,
+    /// Holding the button at least this long reports a [`ButtonEvent::LongPress`]
+    pub long_press: Duration,
+    /// A second press starting within this window after a release reports a
+    /// [`ButtonEvent::DoublePress`] instead of two short presses
+    pub double_press: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(30),
+            long_press: Duration::from_millis(800),
+            double_press: Duration::from_millis(300),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// first press is down
+    Down { since: Duration },
+    /// first press was released; waiting to see if a second one follows
+    Released { at: Duration },
+    /// a gesture has been reported; ignore everything until the button is let go
+    WaitRelease,
+}
+
+/// Turns timestamped button edges into short, long and double presses
+///
+/// Timestamps are durations since an arbitrary, monotonic epoch. Edges are fed
+/// through [`edge()`](Self::edge) as they happen, while [`poll()`](Self::poll)
+/// must be called regularly, as long and short presses are only resolved once
+/// their thresholds have elapsed.
+///
+/// A short press is reported once the double press window has passed without
+/// a second press, so it lags the release by [`GestureConfig::double_press`].
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    state: State,
+    is_down: bool,
+    last_edge: Option<Duration>,
+    /// An edge that arrived within the debounce interval, applied if it still holds after it
+    pending: Option<(bool, Duration)>,
+    events: VecDeque<ButtonEvent>,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new(GestureConfig::default())
+    }
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            state: State::Idle,
+            is_down: false,
+            last_edge: None,
+            pending: None,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if the last accepted edge left the button down
+    pub fn is_down(&self) -> bool {
+        self.is_down
+    }
+
+    /// Records the button going down (`is_down`) or up at the given time
+    ///
+    /// Edges that don't change the button state are ignored. An edge within the
+    /// debounce interval of the previous accepted edge is held back, and only
+    /// applied if no edge back to the previous state follows before it ends.
+    pub fn edge(&mut self, is_down: bool, at: Duration) {
+        self.apply_pending(at);
+        if is_down == self.is_down {
+            // bounced back before the interval ended
+            self.pending = None;
+            return;
+        }
+        if self.last_edge.is_some_and(|last| at.saturating_sub(last) < self.config.debounce) {
+            self.pending = Some((is_down, at));
+            return;
+        }
+
+        self.apply(is_down, at);
+    }
+
+    /// Records a complete press and release at the given time, for when the
+    /// individual edges were missed
+    pub fn click(&mut self, at: Duration) {
+        self.apply_pending(at);
+        if self.is_down {
+            return;
+        }
+
+        self.apply(true, at);
+        self.apply(false, at);
+    }
+
+    /// Returns the next recognized gesture, resolving any thresholds passed by `now`
+    pub fn poll(&mut self, now: Duration) -> Option<ButtonEvent> {
+        self.apply_pending(now);
+        self.resolve_timeouts(now);
+        self.events.pop_front()
+    }
+
+    /// Applies the held back edge if the debounce interval has ended by `now`
+    fn apply_pending(&mut self, now: Duration) {
+        let Some((is_down, at)) = self.pending else {
+            return;
+        };
+        if self.last_edge.is_none_or(|last| now.saturating_sub(last) >= self.config.debounce) {
+            self.pending = None;
+            self.apply(is_down, at);
+        }
+    }
+
+    fn apply(&mut self, is_down: bool, at: Duration) {
+        // resolve anything that timed out before this edge
+        self.resolve_timeouts(at);
+
+        self.is_down = is_down;
+        self.last_edge = Some(at);
+
+        self.state = match (self.state, is_down) {
+            (State::Idle, true) => State::Down { since: at },
+            (State::Down { .. }, false) => State::Released { at },
+            (State::Released { .. }, true) => {
+                self.events.push_back(ButtonEvent::DoublePress);
+                State::WaitRelease
+            }
+            (State::WaitRelease, false) => State::Idle,
+            (state, _) => state,
+        };
+    }
+
+    fn resolve_timeouts(&mut self, now: Duration) {
+        match self.state {
+            State::Down { since } if now.saturating_sub(since) >= self.config.long_press => {
+                self.events.push_back(ButtonEvent::LongPress);
+                self.state = State::WaitRelease;
+            }
+            State::Released { at } if now.saturating_sub(at) >= self.config.double_press => {
+                self.events.push_back(ButtonEvent::Press);
+                self.state = State::Idle;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// Feeds `(is_down, at_ms)` edges and collects all gestures, polling every 10ms
+    fn recognize(edges: &[(bool, u64)], until_ms: u64) -> Vec<(u64, ButtonEvent)> {
+        let mut recognizer = GestureRecognizer::default();
+        let mut edges = edges.iter().peekable();
+        let mut events = Vec::new();
+
+        for now in (0..=until_ms).step_by(10) {
+            while let Some(&&(is_down, at)) = edges.peek() {
+                if at > now {
+                    break;
+                }
+                recognizer.edge(is_down, ms(at));
+                edges.next();
+            }
+            while let Some(event) = recognizer.poll(ms(now)) {
+                events.push((now, event));
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn test_short_press_reported_after_double_press_window() {
+        let events = recognize(&[(true, 100), (false, 200)], 1000);
+        assert_eq!(events, [(500, ButtonEvent::Press)]);
+    }
+
+    #[test]
+    fn test_long_press_reported_while_held() {
+        let events = recognize(&[(true, 100), (false, 1500)], 2000);
+        assert_eq!(events, [(900, ButtonEvent::LongPress)]);
+    }
+
+    #[test]
+    fn test_double_press() {
+        let events = recognize(&[(true, 100), (false, 200), (true, 350), (false, 450)], 1500);
+        assert_eq!(events, [(350, ButtonEvent::DoublePress)]);
+    }
+
+    #[test]
+    fn test_presses_outside_window_are_separate() {
+        let events = recognize(&[(true, 100), (false, 200), (true, 600), (false, 700)], 1500);
+        assert_eq!(events, [(500, ButtonEvent::Press), (1000, ButtonEvent::Press)]);
+    }
+
+    #[test]
+    fn test_bounce_is_ignored() {
+        // contact bounce right after the press and release
+        let edges = [
+            (true, 100), (false, 105), (true, 110),
+            (false, 200), (true, 210), (false, 220),
+        ];
+        let events = recognize(&edges, 1000);
+        assert_eq!(events, [(500, ButtonEvent::Press)]);
+    }
+
+    #[test]
+    fn test_tap_shorter_than_debounce() {
+        // the release arrives within the debounce interval of the press
+        let events = recognize(&[(true, 100), (false, 115)], 2000);
+        assert_eq!(events, [(420, ButtonEvent::Press)]);
+    }
+
+    #[test]
+    fn test_pending_press_resolved_by_late_edge() {
+        // no poll between release and the next press; the first press must not be lost
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.edge(true, ms(0));
+        recognizer.edge(false, ms(100));
+        recognizer.edge(true, ms(1000));
+
+        assert_eq!(recognizer.poll(ms(1000)), Some(ButtonEvent::Press));
+        assert_eq!(recognizer.poll(ms(1000)), None);
+        assert!(recognizer.is_down());
+    }
+
+    #[test]
+    fn test_missed_edges_reported_as_click() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.click(ms(100));
+        assert_eq!(recognizer.poll(ms(200)), None);
+        assert_eq!(recognizer.poll(ms(400)), Some(ButtonEvent::Press));
+
+        recognizer.click(ms(1000));
+        recognizer.click(ms(1100));
+        assert_eq!(recognizer.poll(ms(1100)), Some(ButtonEvent::DoublePress));
+        assert!(!recognizer.is_down());
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+
+/// Button gestures consumed by the scenes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// A short press, not followed by a second one
+    Press,
+    /// Two short presses in quick succession
+    DoublePress,
+    /// The button has been held down past the long press threshold
+    LongPress,
+}
+
+/// Source of button events, polled once per frame by the scenes
+pub trait ButtonInput {
+    /// Returns the next pending event, without blocking
+    fn poll(&mut self) -> Option<ButtonEvent>;
+}
+
+impl<T: ButtonInput + ?Sized> ButtonInput for &mut T {
+    fn poll(&mut self) -> Option<ButtonEvent> {
+        (**self).poll()
+    }
+}
+
+/// Replays a fixed script of button events, for host runs and tests
+///
+/// Events are scheduled against the number of times [`poll()`](ButtonInput::poll)
+/// has been called; since scenes poll once per frame, this amounts to
+/// scheduling by frame.
+#[derive(Debug, Default, Clone)]
+pub struct ScriptedInput {
+    polls: usize,
+    script: VecDeque<(usize, ButtonEvent)>,
+}
+
+impl ScriptedInput {
+    /// Creates an input without any scheduled events
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedules `event` to be returned by the `poll` call with the given (0-based) index
+    ///
+    /// Events must be scheduled in order; an event scheduled before a previous
+    /// one is delivered right after it.
+    pub fn at(mut self, poll: usize, event: ButtonEvent) -> Self {
+        self.script.push_back((poll, event));
+        self
+    }
+
+    /// Queues `event` to be returned by the next `poll` call
+    pub fn push(&mut self, event: ButtonEvent) {
+        self.script.push_back((self.polls, event));
+    }
+
+    /// Number of times the input has been polled
+    pub fn polls(&self) -> usize {
+        self.polls
+    }
+
+    /// Returns `true` once every scheduled event has been delivered
+    pub fn is_exhausted(&self) -> bool {
+        self.script.is_empty()
+    }
+}
+
+impl ButtonInput for ScriptedInput {
+    fn poll(&mut self) -> Option<ButtonEvent> {
+        let poll = self.polls;
+        self.polls += 1;
+
+        match self.script.front() {
+            Some(&(at, event)) if at <= poll => {
+                self.script.pop_front();
+                Some(event)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "esp")]
+pub use gpio::GpioButton;
+
+#[cfg(feature = "esp")]
+mod gpio {
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use esp_idf_svc::hal::delay;
+    use esp_idf_svc::hal::gpio::{Gpio0, Input, InterruptType, PinDriver};
+    use esp_idf_svc::hal::task::notification::Notification;
+    use esp_idf_svc::sys::{esp_timer_get_time, EspError};
+    use crate::gesture::{GestureConfig, GestureRecognizer};
+    use super::{ButtonEvent, ButtonInput};
+
+    /// Time since boot, from the 64-bit microsecond timer
+    fn uptime() -> Duration {
+        Duration::from_micros(unsafe { esp_timer_get_time() } as u64)
+    }
+
+    /// Milliseconds since boot, wrapping after ~49 days; the ESP32 has no 64-bit atomics
+    fn uptime_ms() -> u32 {
+        uptime().as_millis() as u32
+    }
+
+    /// The uptime at `edge_ms`, an [`uptime_ms()`] taken less than ~49 days ago
+    fn edge_time(edge_ms: u32) -> Duration {
+        // read after `edge_ms` was loaded, so that the edge is never ahead of it
+        let now = uptime();
+        let age = (now.as_millis() as u32).wrapping_sub(edge_ms);
+        now.saturating_sub(Duration::from_millis(u64::from(age)))
+    }
+
+    /// The S1 (GPIO0) button, with presses recognized from its edge interrupts
+    ///
+    /// The interrupt handler timestamps each edge; the pin level is sampled when
+    /// polling, and the interrupt re-armed.
+    pub struct GpioButton<'d> {
+        pin: PinDriver<'d, Gpio0, Input>,
+        notification: Notification,
+        last_edge_ms: Arc<AtomicU32>,
+        gestures: GestureRecognizer,
+    }
+
+    impl GpioButton<'static> {
+        /// Configures GPIO0 as input and subscribes to both of its edges
+        pub fn new(pin: Gpio0, config: GestureConfig) -> Result<Self, EspError> {
+            let mut pin = PinDriver::input(pin)?;
+            pin.set_interrupt_type(InterruptType::AnyEdge)?;
+
+            let notification = Notification::new();
+            let notifier = notification.notifier();
+            let last_edge_ms = Arc::new(AtomicU32::new(0));
+            let edge_ms = last_edge_ms.clone();
+            unsafe {
+                pin.subscribe(move || {
+                    edge_ms.store(uptime_ms(), Ordering::Relaxed);
+                    notifier.notify_and_yield(NonZeroU32::new(1).unwrap());
+                })?;
+            }
+            pin.enable_interrupt()?;
+
+            Ok(Self {
+                pin,
+                notification,
+                last_edge_ms,
+                gestures: GestureRecognizer::new(config),
+            })
+        }
+    }
+
+    impl ButtonInput for GpioButton<'_> {
+        fn poll(&mut self) -> Option<ButtonEvent> {
+            if self.notification.wait(delay::NON_BLOCK).is_some() {
+                let at = edge_time(self.last_edge_ms.load(Ordering::Relaxed));
+                // S1 pulls the pin low when pressed
+                let is_down = self.pin.is_low();
+                if is_down != self.gestures.is_down() {
+                    self.gestures.edge(is_down, at);
+                } else if !is_down {
+                    // a full press happened before we got around to polling
+                    self.gestures.click(at);
+                }
+
+                // the interrupt is disabled after firing
+                self.pin.enable_interrupt().unwrap();
+            }
+
+            self.gestures.poll(uptime())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripted_input_delivers_at_scheduled_poll() {
+        let mut input = ScriptedInput::new()
+            .at(2, ButtonEvent::Press)
+            .at(4, ButtonEvent::DoublePress);
+
+        let events: Vec<_> = (0..6).map(|_| input.poll()).collect();
+        assert_eq!(events, [
+            None,
+            None,
+            Some(ButtonEvent::Press),
+            None,
+            Some(ButtonEvent::DoublePress),
+            None,
+        ]);
+        assert!(input.is_exhausted());
+        assert_eq!(input.polls(), 6);
+    }
+
+    #[test]
+    fn test_scripted_input_push_delivers_on_next_poll() {
+        let mut input = ScriptedInput::new();
+        assert_eq!(input.poll(), None);
+
+        input.push(ButtonEvent::LongPress);
+        input.push(ButtonEvent::Press);
+        assert_eq!(input.poll(), Some(ButtonEvent::LongPress));
+        assert_eq!(input.poll(), Some(ButtonEvent::Press));
+        assert_eq!(input.poll(), None);
+    }
+}
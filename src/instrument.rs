@@ -0,0 +1,213 @@
+//! Counters of what ratatui hands to the backend.
+//!
+//! [`InstrumentedBackend`] wraps any backend and counts, per frame, the cells
+//! the buffer diff passed to `draw`, the runs and rows they formed and the
+//! draw and flush calls, along with the time spent in each. The
+//! [`SceneRunner`](crate::scene::SceneRunner) closes every frame, after which
+//! scenes can read the counters of the previous frame with [`last_frame()`].
+
+use std::cell::Cell;
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+use ratatui::backend::{Backend, ClearType, WindowSize};
+use ratatui::buffer::Cell as BufferCell;
+use ratatui::layout::{Position, Size};
+use crate::phases::{self, Phase};
+
+/// Backend work of one frame, or summed over many
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrawStats {
+    /// Cells passed to `draw`
+    pub cells: usize,
+    /// Horizontally contiguous stretches of those cells
+    pub runs: usize,
+    /// Distinct rows touched, counted per `draw` call
+    pub rows: usize,
+    pub draw_calls: usize,
+    pub flushes: usize,
+    pub draw_time: Duration,
+    pub flush_time: Duration,
+}
+
+impl AddAssign for DrawStats {
+    fn add_assign(&mut self, other: Self) {
+        self.cells += other.cells;
+        self.runs += other.runs;
+        self.rows += other.rows;
+        self.draw_calls += other.draw_calls;
+        self.flushes += other.flushes;
+        self.draw_time += other.draw_time;
+        self.flush_time += other.flush_time;
+    }
+}
+
+const NONE: DrawStats = DrawStats {
+    cells: 0,
+    runs: 0,
+    rows: 0,
+    draw_calls: 0,
+    flushes: 0,
+    draw_time: Duration::ZERO,
+    flush_time: Duration::ZERO,
+};
+
+thread_local! {
+    /// Counted since the last frame was closed
+    static CURRENT: Cell<DrawStats> = const { Cell::new(NONE) };
+    static LAST_FRAME: Cell<DrawStats> = const { Cell::new(NONE) };
+}
+
+fn record(f: impl FnOnce(&mut DrawStats)) {
+    CURRENT.with(|current| {
+        let mut stats = current.get();
+        f(&mut stats);
+        current.set(stats);
+    });
+}
+
+/// Counters of the most recently finished frame
+pub fn last_frame() -> DrawStats {
+    LAST_FRAME.with(Cell::get)
+}
+
+/// Closes the current frame, returning its counters
+pub(crate) fn finish_frame() -> DrawStats {
+    let stats = CURRENT.with(Cell::take);
+    LAST_FRAME.with(|last| last.set(stats));
+    stats
+}
+
+/// Backend decorator counting cells, runs, rows, draw and flush calls
+///
+/// The time spent in the wrapped backend is also reported as [`Phase::Flush`];
+/// without the decorator, it is counted as part of the diff.
+#[derive(Debug)]
+pub struct InstrumentedBackend<B> {
+    inner: B,
+}
+
+impl<B: Backend> InstrumentedBackend<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+}
+
+impl<B: Backend> Backend for InstrumentedBackend<B> {
+    type Error = B::Error;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), Self::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a BufferCell)>,
+    {
+        let mut cells = 0;
+        let mut runs = 0;
+        let mut rows = 0;
+        // counting happens lazily, as the wrapped backend pulls the cells
+        let mut last: Option<(u16, u16)> = None;
+        let content = content.inspect(|&(x, y, _)| {
+            cells += 1;
+            match last {
+                Some((last_x, last_y)) if last_y == y => runs += usize::from(x != last_x + 1),
+                _ => {
+                    runs += 1;
+                    rows += 1;
+                }
+            }
+            last = Some((x, y));
+        });
+
+        let start = Instant::now();
+        let result = phases::time(Phase::Flush, || self.inner.draw(content));
+        let draw_time = start.elapsed();
+
+        record(|stats| {
+            stats.cells += cells;
+            stats.runs += runs;
+            stats.rows += rows;
+            stats.draw_calls += 1;
+            stats.draw_time += draw_time;
+        });
+        result
+    }
+
+    fn append_lines(&mut self, n: u16) -> Result<(), Self::Error> {
+        self.inner.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), Self::Error> {
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), Self::Error> {
+        self.inner.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position, Self::Error> {
+        self.inner.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), Self::Error> {
+        self.inner.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.inner.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        self.inner.clear_region(clear_type)
+    }
+
+    fn size(&self) -> Result<Size, Self::Error> {
+        self.inner.size()
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize, Self::Error> {
+        self.inner.window_size()
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let start = Instant::now();
+        let result = phases::time(Phase::Flush, || self.inner.flush());
+        let flush_time = start.elapsed();
+
+        record(|stats| {
+            stats.flushes += 1;
+            stats.flush_time += flush_time;
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::widgets::Paragraph;
+
+    #[test]
+    fn test_counts_changed_cells_runs_and_rows() {
+        let mut terminal = Terminal::new(InstrumentedBackend::new(TestBackend::new(10, 3))).unwrap();
+        finish_frame();
+
+        terminal.draw(|frame| frame.render_widget(Paragraph::new("ab\n\ncd  e"), frame.area())).unwrap();
+        let first = finish_frame();
+        assert_eq!((first.cells, first.runs, first.rows), (5, 3, 2));
+        assert_eq!((first.draw_calls, first.flushes), (1, 1));
+        assert_eq!(last_frame(), first);
+
+        // only the changed cell is drawn again
+        terminal.draw(|frame| frame.render_widget(Paragraph::new("ab\n\ncd  f"), frame.area())).unwrap();
+        let second = finish_frame();
+        assert_eq!((second.cells, second.runs, second.rows), (1, 1, 1));
+    }
+}
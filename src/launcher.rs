@@ -0,0 +1,316 @@
+use ratatui::{Frame, Terminal};
+use ratatui::backend::Backend;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+use compact_str::{format_compact, CompactString};
+use crate::catpuccin::CATPPUCCIN;
+use crate::fps::FpsWidget;
+use crate::header::render_header;
+use crate::input::{ButtonEvent, ButtonInput};
+use crate::phases::PhaseBar;
+use crate::report::Report;
+use crate::scene::{Scene, SceneExit, SceneRegistry, SceneRun, SceneRunner};
+
+/// What the launcher was asked to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Every registered scene, in order
+    All,
+    /// A single scene, by registry name
+    Scene(&'static str),
+}
+
+#[derive(Debug)]
+struct Entry {
+    selection: Selection,
+    result: Option<CompactString>,
+    /// The last run, for its frame phases and display writes
+    run: Option<SceneRun>,
+}
+
+/// Menu of the registered scenes, remembering the last result of each
+///
+/// A press moves to the next entry, a double press to the previous one and a
+/// long press leaves the launcher to run the selected entry.
+#[derive(Debug)]
+pub struct Launcher {
+    entries: Vec<Entry>,
+    state: ListState,
+}
+
+impl Launcher {
+    pub fn new(registry: &SceneRegistry) -> Self {
+        let mut entries = vec![Entry { selection: Selection::All, result: None, run: None }];
+        for name in registry.names() {
+            let selection = Selection::Scene(name);
+            if entries.iter().all(|e| e.selection != selection) {
+                entries.push(Entry { selection, result: None, run: None });
+            }
+        }
+
+        Self {
+            entries,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// The highlighted entry
+    pub fn selected(&self) -> Selection {
+        self.entries[self.state.selected().unwrap_or(0)].selection
+    }
+
+    /// The last result recorded for the scene `name`
+    pub fn result(&self, name: &str) -> Option<&str> {
+        self.entries.iter()
+            .find(|e| matches!(e.selection, Selection::Scene(n) if n == name))
+            .and_then(|e| e.result.as_deref())
+    }
+
+    /// Runs the selected entry, recording the result of every scene that exits
+    /// and adding its run to `report`
+    ///
+    /// Running all scenes stops early if one of them quits the suite.
+    pub fn launch<B: Backend>(
+        &mut self,
+        registry: &SceneRegistry,
+        runner: &mut SceneRunner,
+        terminal: &mut Terminal<B>,
+        input: &mut impl ButtonInput,
+        report: &mut Report,
+    ) -> Result<SceneExit, B::Error> {
+        match self.selected() {
+            Selection::All => {
+                for name in registry.names() {
+                    if self.run_scene(name, registry, runner, terminal, input, report)? == SceneExit::Quit {
+                        return Ok(SceneExit::Quit);
+                    }
+                }
+                Ok(SceneExit::Next)
+            }
+            Selection::Scene(name) => self.run_scene(name, registry, runner, terminal, input, report),
+        }
+    }
+
+    fn run_scene<B: Backend>(
+        &mut self,
+        name: &'static str,
+        registry: &SceneRegistry,
+        runner: &mut SceneRunner,
+        terminal: &mut Terminal<B>,
+        input: &mut impl ButtonInput,
+        report: &mut Report,
+    ) -> Result<SceneExit, B::Error> {
+        let Some(mut scene) = registry.create(name) else {
+            return Ok(SceneExit::Next);
+        };
+
+        let exit = runner.run(scene.as_mut(), terminal, input)?;
+        report.record_stage(name, 1, scene.as_ref(), runner);
+        report.record_microbenchmarks(name, scene.as_ref());
+
+        let result = scene.summary()
+            .or_else(|| runner.last_run().map(|run| format_compact!("{:.1} fps", run.fps())));
+
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.selection == Selection::Scene(name)) {
+            entry.result = result;
+            entry.run = runner.last_run();
+        }
+
+        Ok(exit)
+    }
+
+    fn select_offset(&mut self, offset: usize) {
+        let len = self.entries.len();
+        let selected = self.state.selected().unwrap_or(0);
+        self.state.select(Some((selected + offset) % len));
+    }
+}
+
+impl Scene for Launcher {
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press => self.select_offset(1),
+            ButtonEvent::DoublePress => self.select_offset(self.entries.len() - 1),
+            ButtonEvent::LongPress => return Some(SceneExit::Next),
+        }
+        None
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(3),
+            Constraint::Length(2),
+        ]).split(area);
+
+        render_header(layout[0], buf, "Mousefood Benchmarks", CATPPUCCIN.mauve);
+        self.render_list(layout[1].inner(Margin::new(6, 0)), buf);
+        self.render_phases(layout[2].inner(Margin::new(6, 0)), buf);
+        self.render_footer(layout[3], buf, fps);
+    }
+}
+
+impl Launcher {
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let items = self.entries.iter().map(|entry| {
+            let name = match entry.selection {
+                Selection::All => "run all",
+                Selection::Scene(name) => name,
+            };
+            let result = match (&entry.result, entry.selection) {
+                (Some(result), _) => Span::styled(result.as_str(), Style::default().fg(CATPPUCCIN.green)),
+                (None, Selection::All) => Span::raw(""),
+                (None, Selection::Scene(_)) => Span::styled("---", Style::default().fg(CATPPUCCIN.surface2)),
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format_compact!("{name:<16}"), Style::default().fg(CATPPUCCIN.text)),
+                result,
+            ]))
+        });
+
+        let list = List::new(items)
+            .highlight_symbol("> ")
+            .highlight_style(Style::default().bg(CATPPUCCIN.surface0));
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+
+    /// Where the frame time of the selected scene's last run went, and how
+    /// much of it the SPI transfer alone would take
+    fn render_phases(&self, area: Rect, buf: &mut Buffer) {
+        let entry = &self.entries[self.state.selected().unwrap_or(0)];
+        let Some(run) = &entry.run else {
+            return;
+        };
+
+        let [bar_area, spi_area] = Layout::vertical([Constraint::Length(2), Constraint::Length(1)]).areas(area);
+        PhaseBar::new(&run.phases).render(bar_area, buf);
+
+        if run.frames > 0 && !run.pixels.spi_time.is_zero() {
+            let spi = run.pixels.spi_time.as_secs_f32();
+            let text = format_compact!(
+                "spi {:.2} ms/frame, {:.0}% of frame",
+                spi * 1000.0 / run.frames as f32,
+                spi * 100.0 / run.elapsed.as_secs_f32().max(f32::EPSILON),
+            );
+            buf.set_stringn(spi_area.x, spi_area.y, text.as_str(), spi_area.width as usize, Style::default().fg(CATPPUCCIN.subtext0));
+        }
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
+        let [hint_area, fps_area] = Layout::vertical([Constraint::Length(1); 2]).areas(area);
+
+        Paragraph::new("press: next  hold: run")
+            .style(Style::default().fg(CATPPUCCIN.subtext0))
+            .render(hint_area.inner(Margin::new(8, 0)), buf);
+        fps.render(fps_area.inner(Margin::new(8, 0)), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use crate::input::ScriptedInput;
+
+    struct Fixed(&'static str);
+
+    impl Scene for Fixed {
+        fn render(&mut self, _frame: &mut Frame, _fps: &FpsWidget) {}
+
+        fn summary(&self) -> Option<CompactString> {
+            Some(self.0.into())
+        }
+    }
+
+    struct Idle;
+
+    impl Scene for Idle {
+        fn render(&mut self, _frame: &mut Frame, _fps: &FpsWidget) {}
+    }
+
+    fn registry() -> SceneRegistry {
+        SceneRegistry::new()
+            .register("fixed", || Fixed("12 ms"))
+            .register("idle", || Idle)
+    }
+
+    fn screen(terminal: &Terminal<TestBackend>) -> String {
+        let buf = terminal.backend().buffer();
+        buf.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_press_and_double_press_wrap_around() {
+        let mut launcher = Launcher::new(&registry().select("idle,fixed,idle").unwrap());
+        assert_eq!(launcher.entries.len(), 3);
+        assert_eq!(launcher.selected(), Selection::All);
+
+        assert_eq!(launcher.handle_input(ButtonEvent::DoublePress), None);
+        assert_eq!(launcher.selected(), Selection::Scene("fixed"));
+        assert_eq!(launcher.handle_input(ButtonEvent::Press), None);
+        assert_eq!(launcher.selected(), Selection::All);
+        assert_eq!(launcher.handle_input(ButtonEvent::Press), None);
+        assert_eq!(launcher.selected(), Selection::Scene("idle"));
+        assert_eq!(launcher.handle_input(ButtonEvent::LongPress), Some(SceneExit::Next));
+    }
+
+    #[test]
+    fn test_launch_records_results() {
+        let registry = registry();
+        let mut launcher = Launcher::new(&registry);
+        let mut runner = SceneRunner::new();
+        let mut report = Report::default();
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut input = ScriptedInput::new()
+            .at(1, ButtonEvent::LongPress)
+            .at(3, ButtonEvent::LongPress);
+
+        runner.run(&mut launcher, &mut terminal, &mut input).unwrap();
+        let exit = launcher.launch(&registry, &mut runner, &mut terminal, &mut input, &mut report).unwrap();
+
+        assert_eq!(exit, SceneExit::Quit);
+        assert_eq!(launcher.result("fixed"), Some("12 ms"));
+        assert_eq!(launcher.result("idle"), None);
+        assert!(launcher.entries[1].run.is_some());
+
+        launcher.handle_input(ButtonEvent::Press);
+        launcher.handle_input(ButtonEvent::Press);
+        let mut input = ScriptedInput::new().at(2, ButtonEvent::Press);
+        launcher.launch(&registry, &mut runner, &mut terminal, &mut input, &mut report).unwrap();
+        assert_eq!(launcher.result("fixed"), Some("12 ms"));
+        assert!(launcher.result("idle").is_some_and(|r| r.ends_with(" fps")));
+
+        let scenes: Vec<_> = report.stages.iter().map(|s| s.scene).collect();
+        assert_eq!(scenes, ["fixed", "idle"]);
+    }
+
+    #[test]
+    fn test_render_shows_results() {
+        let registry = registry();
+        let mut launcher = Launcher::new(&registry);
+        let mut runner = SceneRunner::new();
+        let mut report = Report::default();
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+
+        launcher.handle_input(ButtonEvent::Press);
+        let mut input = ScriptedInput::new().at(0, ButtonEvent::Press);
+        launcher.launch(&registry, &mut runner, &mut terminal, &mut input, &mut report).unwrap();
+
+        let mut input = ScriptedInput::new().at(1, ButtonEvent::LongPress);
+        runner.run(&mut launcher, &mut terminal, &mut input).unwrap();
+
+        let screen = screen(&terminal);
+        assert!(screen.contains("> fixed           12 ms"));
+        assert!(screen.contains("idle            ---"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Benchmark scenes and utilities for mousefood and tachyonfx.
+//!
+//! Everything in here builds on the host; the ESP-IDF specific parts are
+//! gated behind the `esp` feature and only used by the firmware binary.
+
+pub mod autorun;
+pub mod benchmark;
+pub mod catpuccin;
+pub mod cell_store;
+pub mod compute;
+pub mod corpus;
+pub mod diff;
+pub mod dirty;
+pub mod embedded_str;
+pub mod export;
+pub mod fps;
+pub mod framebuffer;
+pub mod gallery;
+pub mod gesture;
+pub mod gauge;
+pub mod glyph_mapping;
+pub mod input;
+pub mod instrument;
+pub mod launcher;
+pub mod microbench;
+pub mod nonsense;
+pub mod phases;
+pub mod platform;
+pub mod plot;
+pub mod report;
+pub mod scene;
+pub mod snapshot;
+pub mod stats;
+pub mod string_ops;
+pub mod throughput;
+pub mod worm_bench;
+pub mod worm_buffer;
+
+mod header;
+
+/// Offset of the visible area within the ST7789 controller's memory
+pub const DISPLAY_OFFSET: (u16, u16) = (0, 0);
+
+/// Panel size in pixels, before the 90° rotation applied in the firmware
+pub const DISPLAY_SIZE: (u16, u16) = (
+    240 - DISPLAY_OFFSET.0 * 2,
+    320 - DISPLAY_OFFSET.1 * 0,
+);
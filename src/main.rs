@@ -1,54 +1,32 @@
-
-mod gauge;
-mod lorem;
-mod fps;
-mod catpuccin;
-mod stats;
-mod benchmark;
-mod nonsense;
-mod compute;
-mod glyph_mapping;
-mod string_ops;
-mod embedded_str;
-mod header;
-mod worm_buffer;
-
-use crate::gauge::GaugeApp;
-use crate::benchmark::Benchmark;
-use crate::nonsense::Nonsense;
-use crate::compute::ComputeApp;
-use crate::glyph_mapping::GlyphMappingApp;
-use crate::string_ops::StringOpsApp;
+#[cfg(feature = "autorun")]
+use mousefood_benchmark::autorun::{self, AutoRun, Budget};
+use mousefood_benchmark::{gesture::GestureConfig, input::GpioButton, launcher::Launcher};
+use mousefood_benchmark::export::{Exporter, RunInfo};
+use mousefood_benchmark::instrument::InstrumentedBackend;
+use mousefood_benchmark::report::Report;
+use mousefood_benchmark::scene::{SceneRegistry, SceneRunner};
+use mousefood_benchmark::throughput::{CountingTarget, SpiModel};
+use mousefood_benchmark::{DISPLAY_OFFSET, DISPLAY_SIZE};
 use esp_idf_svc::hal::adc::Resolution;
 use esp_idf_svc::hal::adc::attenuation::DB_11;
 use esp_idf_svc::hal::adc::oneshot::config::{AdcChannelConfig, Calibration};
 use esp_idf_svc::hal::adc::oneshot::{AdcChannelDriver, AdcDriver};
 use esp_idf_svc::hal::delay::Ets;
-use esp_idf_svc::hal::gpio::{AnyIOPin, InterruptType, PinDriver};
+use esp_idf_svc::hal::gpio::{AnyIOPin, PinDriver};
 use esp_idf_svc::hal::prelude::*;
 use esp_idf_svc::hal::spi::config::MODE_3;
 use esp_idf_svc::hal::spi::{SpiConfig, SpiDeviceDriver, SpiDriverConfig};
-use esp_idf_svc::hal::task::notification::Notification;
 use mipidsi::Builder;
 use mipidsi::interface::SpiInterface;
 use mipidsi::models::ST7789;
 use mipidsi::options::{ColorInversion, Orientation, Rotation};
 use mousefood::prelude::*;
-use std::num::{NonZeroU32, NonZeroUsize};
-use std::thread;
-use std::time::Duration;
+use std::num::NonZeroUsize;
 use embedded_graphics::prelude::{DrawTarget, Point, Size};
 use embedded_graphics::primitives::Rectangle;
-use embedded_graphics_unicodefonts::{mono_6x10_atlas, mono_6x10_optimized_atlas, mono_6x13_bold_atlas};
+use embedded_graphics_unicodefonts::{mono_6x10_optimized_atlas, mono_6x13_bold_atlas};
 use ratatui::Terminal;
 use ratatui::layout::Layout;
-use crate::stats::Stats;
-
-const DISPLAY_OFFSET: (u16, u16) = (0, 0);
-const DISPLAY_SIZE: (u16, u16) = (
-    240 - DISPLAY_OFFSET.0 * 2,
-    320 - DISPLAY_OFFSET.1 * 0,
-);
 
 fn main() {
     esp_idf_svc::sys::link_patches();
@@ -94,17 +72,7 @@ fn main() {
         .expect("Failed to init display");
 
     // Setup button interrupt
-    let mut button = PinDriver::input(peripherals.pins.gpio0).unwrap();
-    button.set_interrupt_type(InterruptType::NegEdge).unwrap();
-    let mut notification = Notification::new();
-    let notifier = notification.notifier();
-    unsafe {
-        button
-            .subscribe(move || {
-                notifier.notify_and_yield(NonZeroU32::new(1).unwrap());
-            })
-            .unwrap();
-    }
+    let mut button = GpioButton::new(peripherals.pins.gpio0, GestureConfig::default()).unwrap();
 
     // Setup battery voltage reader
     let adc_driver = AdcDriver::new(peripherals.adc1).unwrap();
@@ -132,50 +100,49 @@ fn main() {
         Rgb565::new(0, 0, 0)
     ).unwrap();
 
-    let backend = EmbeddedBackend::new(&mut display, config);
+    // counts the pixels reaching the ST7789 and estimates their bus time at the
+    // 80 MHz and 8192-byte buffer configured above
+    let mut display = CountingTarget::new(display, SpiModel::default());
+
+    // counts and times the draw and flush through mousefood to the ST7789
+    let backend = InstrumentedBackend::new(EmbeddedBackend::new(&mut display, config));
     let mut terminal = Terminal::new(backend).unwrap();
     Layout::init_cache(NonZeroUsize::new(20).unwrap()); // default is 500
 
-    loop {
-        Stats::new()
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-        thread::sleep(Duration::from_millis(200));
-
-        Nonsense::new()
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-
-        thread::sleep(Duration::from_millis(200));
-
-        ComputeApp::new()
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-
-        thread::sleep(Duration::from_millis(200));
-
-        GlyphMappingApp::new(&mono_6x10_optimized_atlas())
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-
-        thread::sleep(Duration::from_millis(200));
-
-        StringOpsApp::new(&mono_6x10_atlas())
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-
-        thread::sleep(Duration::from_millis(200));
-
-        Benchmark::new()
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
-
-        thread::sleep(Duration::from_millis(200));
-
-        GaugeApp::new()
-            .run(&mut terminal, &mut notification, &mut button)
-            .unwrap();
+    // the run order can be changed at build time, e.g. MOUSEFOOD_SCENES=text,gauge
+    let registry = match option_env!("MOUSEFOOD_SCENES") {
+        Some(names) => SceneRegistry::builtin().select(names).unwrap(),
+        None => SceneRegistry::builtin(),
+    };
+
+    // the fps counter may show ms per frame or the p99 frame time instead, e.g. MOUSEFOOD_FPS_MODE=p99
+    let fps_mode = option_env!("MOUSEFOOD_FPS_MODE").unwrap_or("fps").parse().unwrap();
+    let mut runner = SceneRunner::new().with_fps_mode(fps_mode);
+
+    // results are also logged as records, e.g. MOUSEFOOD_EXPORT=csv; defaults to JSON Lines
+    let format = option_env!("MOUSEFOOD_EXPORT").unwrap_or("jsonl").parse().unwrap();
+    let mut exporter = Exporter::new(format, RunInfo::new("mono_6x10_optimized_atlas"));
+    let mut stdout = std::io::stdout();
+
+    // unattended: cycle through every scene and stage, reporting after each round, then
+    // hand over to the launcher
+    #[cfg(feature = "autorun")]
+    {
+        let rounds = autorun::rounds_from_env();
+        let autorun = AutoRun::new(Budget::from_env());
+        for round in (1..).take_while(|&round| rounds.is_none_or(|rounds| round <= rounds)) {
+            let report = autorun.run(&registry, &mut runner, &mut terminal).unwrap();
+            println!("autorun round {round}\n{report}");
+            exporter.write_report(&report, &mut stdout).unwrap();
+        }
+    }
 
-        thread::sleep(Duration::from_millis(200));
+    // the launcher lists the registry; holding the button in a scene returns to it
+    let mut launcher = Launcher::new(&registry);
+    loop {
+        runner.run(&mut launcher, &mut terminal, &mut button).unwrap();
+        let mut report = Report::default();
+        launcher.launch(&registry, &mut runner, &mut terminal, &mut button, &mut report).unwrap();
+        exporter.write_report(&report, &mut stdout).unwrap();
     }
 }
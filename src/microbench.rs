@@ -0,0 +1,325 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Times a closure over several samples after discarding warmup runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Harness {
+    /// Untimed runs before the first sample
+    pub warmup: usize,
+    /// Timed runs
+    pub samples: usize,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self { warmup: 1, samples: 10 }
+    }
+}
+
+impl Harness {
+    pub fn new(warmup: usize, samples: usize) -> Self {
+        Self { warmup, samples: samples.max(1) }
+    }
+
+    /// Runs `f(iterations)` `warmup + samples` times, timing the last `samples` runs
+    pub fn measure(&self, iterations: u32, mut f: impl FnMut(u32)) -> Measurement {
+        for _ in 0..self.warmup {
+            f(iterations);
+        }
+
+        let samples = (0..self.samples)
+            .map(|_| {
+                let start = Instant::now();
+                f(iterations);
+                start.elapsed()
+            })
+            .collect();
+
+        Measurement::new(iterations, samples)
+    }
+}
+
+/// Timed samples of one microbenchmark, each covering `iterations` iterations
+///
+/// `Display` shows the median in ns per iteration and the relative standard
+/// deviation, e.g. `12.4±3%`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub iterations: u32,
+    /// In the order they were taken
+    pub samples: Vec<Duration>,
+}
+
+impl Measurement {
+    pub fn new(iterations: u32, samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "a measurement needs at least one sample");
+        Self { iterations, samples }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().copied().min().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.iter().copied().max().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+
+        let mid = sorted.len() / 2;
+        if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.total() / self.samples.len() as u32
+    }
+
+    /// Population standard deviation of the samples
+    pub fn stddev(&self) -> Duration {
+        let mean = self.mean().as_secs_f64();
+        let variance = self.samples.iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>() / self.samples.len() as f64;
+
+        Duration::from_secs_f64(variance.sqrt())
+    }
+
+    /// Time spent in all samples together
+    pub fn total(&self) -> Duration {
+        self.samples.iter().sum()
+    }
+
+    /// Median time per iteration, in nanoseconds
+    pub fn ns_per_iter(&self) -> f64 {
+        self.median().as_secs_f64() * 1e9 / f64::from(self.iterations.max(1))
+    }
+
+    /// Standard deviation relative to the mean, in percent
+    pub fn spread(&self) -> f64 {
+        let mean = self.mean().as_secs_f64();
+        if mean == 0.0 {
+            return 0.0;
+        }
+        self.stddev().as_secs_f64() / mean * 100.0
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ns = self.ns_per_iter();
+        let spread = self.spread().round().min(99.0);
+        match ns {
+            ns if ns < 10.0 => write!(f, "{ns:.2}±{spread}%"),
+            ns if ns < 1000.0 => write!(f, "{ns:.1}±{spread}%"),
+            ns => write!(f, "{ns:.0}±{spread}%"),
+        }
+    }
+}
+
+/// A microbenchmark, declared once with everything needed to run and display it
+pub struct Microbench {
+    /// Row label, e.g. `ADD`
+    pub name: &'static str,
+    /// Column label, e.g. `u32`
+    pub group: &'static str,
+    /// Iterations per sample
+    pub iterations: u32,
+    run: Box<dyn FnMut(u32)>,
+}
+
+impl fmt::Debug for Microbench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Microbench")
+            .field("name", &self.name)
+            .field("group", &self.group)
+            .field("iterations", &self.iterations)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where a microbenchmark of a [`Suite`] stands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchState<'a> {
+    Pending,
+    /// Next in line; runs on the following [`Suite::run_next()`]
+    Running,
+    Done(&'a Measurement),
+}
+
+/// Microbenchmarks run one at a time, in declaration order
+#[derive(Debug)]
+pub struct Suite {
+    harness: Harness,
+    benches: Vec<Microbench>,
+    /// One per finished benchmark, in the same order
+    results: Vec<Measurement>,
+}
+
+impl Suite {
+    pub fn new(harness: Harness) -> Self {
+        Self { harness, benches: Vec::new(), results: Vec::new() }
+    }
+
+    /// Declares a benchmark; `run(n)` performs `n` iterations and is timed as one sample
+    pub fn bench(
+        mut self,
+        name: &'static str,
+        group: &'static str,
+        iterations: u32,
+        run: impl FnMut(u32) + 'static,
+    ) -> Self {
+        self.benches.push(Microbench { name, group, iterations, run: Box::new(run) });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.benches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.benches.is_empty()
+    }
+
+    /// Number of finished benchmarks
+    pub fn completed(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.completed() == self.len()
+    }
+
+    /// Measures the next pending benchmark, if any
+    pub fn run_next(&mut self) -> Option<&Measurement> {
+        let bench = self.benches.get_mut(self.results.len())?;
+        let measurement = self.harness.measure(bench.iterations, &mut bench.run);
+        self.results.push(measurement);
+        self.results.last()
+    }
+
+    /// State of the benchmark declared as `name` in `group`
+    pub fn state(&self, name: &str, group: &str) -> Option<BenchState<'_>> {
+        let idx = self.benches.iter().position(|b| b.name == name && b.group == group)?;
+        Some(match self.results.get(idx) {
+            Some(measurement) => BenchState::Done(measurement),
+            None if idx == self.results.len() => BenchState::Running,
+            None => BenchState::Pending,
+        })
+    }
+
+    /// Measurement of the benchmark declared as `name` in `group`
+    pub fn result(&self, name: &str, group: &str) -> Option<&Measurement> {
+        match self.state(name, group)? {
+            BenchState::Done(measurement) => Some(measurement),
+            _ => None,
+        }
+    }
+
+    /// Distinct names of the benchmarks in any of `groups`, in declaration order
+    pub fn names_in(&self, groups: &[&str]) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for bench in self.benches.iter().filter(|b| groups.contains(&b.group)) {
+            if !names.contains(&bench.name) {
+                names.push(bench.name);
+            }
+        }
+        names
+    }
+
+    /// Declared benchmarks with their measurement, if finished
+    pub fn iter(&self) -> impl Iterator<Item = (&Microbench, Option<&Measurement>)> {
+        self.benches.iter()
+            .enumerate()
+            .map(|(idx, bench)| (bench, self.results.get(idx)))
+    }
+
+    /// Time spent in all samples of the finished benchmarks
+    pub fn total(&self) -> Duration {
+        self.results.iter().map(Measurement::total).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(samples: &[u64]) -> Measurement {
+        Measurement::new(1000, samples.iter().copied().map(Duration::from_millis).collect())
+    }
+
+    #[test]
+    fn test_statistics() {
+        let m = ms(&[4, 2, 6, 8]);
+        assert_eq!(m.min(), Duration::from_millis(2));
+        assert_eq!(m.max(), Duration::from_millis(8));
+        assert_eq!(m.median(), Duration::from_millis(5));
+        assert_eq!(m.mean(), Duration::from_millis(5));
+        assert_eq!(m.total(), Duration::from_millis(20));
+        assert!((m.stddev().as_secs_f64() * 1e3 - 5f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_median_of_odd_count() {
+        assert_eq!(ms(&[9, 1, 5]).median(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_ns_per_iter_and_display() {
+        let m = ms(&[3, 3, 3]);
+        assert_eq!(m.ns_per_iter(), 3000.0);
+        assert_eq!(m.spread(), 0.0);
+        assert_eq!(m.to_string(), "3000±0%");
+
+        let m = Measurement::new(1_000_000, vec![Duration::from_millis(2), Duration::from_millis(4)]);
+        assert_eq!(m.to_string(), "3.00±33%");
+    }
+
+    fn suite() -> Suite {
+        Suite::new(Harness::new(0, 2))
+            .bench("add", "u32", 10, |_| {})
+            .bench("mul", "u32", 10, |_| {})
+            .bench("add", "f32", 10, |_| {})
+            .bench("alloc", "format", 10, |_| {})
+    }
+
+    #[test]
+    fn test_suite_runs_in_declaration_order() {
+        let mut suite = suite();
+        assert_eq!(suite.state("add", "u32"), Some(BenchState::Running));
+        assert_eq!(suite.state("add", "f32"), Some(BenchState::Pending));
+        assert_eq!(suite.state("div", "u32"), None);
+
+        suite.run_next();
+        suite.run_next();
+        assert_eq!(suite.completed(), 2);
+        assert!(suite.result("mul", "u32").is_some());
+        assert_eq!(suite.state("add", "f32"), Some(BenchState::Running));
+
+        while suite.run_next().is_some() {}
+        assert!(suite.is_done());
+        assert_eq!(suite.iter().filter(|(_, m)| m.is_some()).count(), 4);
+    }
+
+    #[test]
+    fn test_suite_names_in_groups() {
+        let suite = suite();
+        assert_eq!(suite.names_in(&["u32", "f32"]), ["add", "mul"]);
+        assert_eq!(suite.names_in(&["format"]), ["alloc"]);
+    }
+
+    #[test]
+    fn test_harness_runs_warmup_and_samples() {
+        let mut calls = Vec::new();
+        let m = Harness::new(2, 3).measure(7, |n| calls.push(n));
+
+        assert_eq!(calls, [7; 5]);
+        assert_eq!(m.samples.len(), 3);
+        assert_eq!(m.iterations, 7);
+    }
+}
@@ -1,12 +1,11 @@
 use crate::catpuccin::CATPPUCCIN;
 use crate::fps::FpsWidget;
+use crate::scene::Scene;
 use crate::header::render_header;
+use crate::phases::{self, Phase};
+use crate::platform::memory_info;
 use compact_str::format_compact;
-use esp_idf_svc::hal::delay;
-use esp_idf_svc::hal::gpio::{Gpio0, Input, PinDriver};
-use esp_idf_svc::hal::task::notification::Notification;
-use esp_idf_svc::sys::{heap_caps_get_free_size, heap_caps_get_total_size, MALLOC_CAP_8BIT};
-use ratatui::prelude::{Backend, Color, Terminal};
+use ratatui::prelude::{Color, Frame};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -15,24 +14,24 @@ use ratatui::{
     widgets::{Block, Paragraph, Widget},
 };
 use ratatui::layout::Margin;
-use std::marker::PhantomData;
 use tachyonfx::Motion::{LeftToRight, RightToLeft, UpToDown};
 use tachyonfx::{fx, CellFilter, ColorSpace, Duration, EffectManager, Interpolation, Motion, ToRgbComponents};
+use crate::snapshot::NONSENSE_HEADER;
 use crate::worm_buffer::WormBuffer;
 
-pub struct Nonsense<B: Backend> {
-    fps_widget: FpsWidget,
+pub struct Nonsense {
     effects: EffectManager<()>,
+    effect_delta: Duration,
+    effect_carry: std::time::Duration,
     content: Paragraph<'static>,
     worm_buffer: WormBuffer,
-    _marker: PhantomData<B>,
 }
 
-impl<B: Backend> Nonsense<B> {
+impl Nonsense {
     pub fn new() -> Self {
         let area = Rect::new(0, 3, 53, 15);
 
-        let (free_memory, total_memory) = Self::get_memory_info();
+        let (free_memory, total_memory) = memory_info();
         let used_memory = total_memory - free_memory;
 
         let content = vec![
@@ -43,7 +42,7 @@ impl<B: Backend> Nonsense<B> {
             Line::from(vec![
                 Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
                 Span::styled("Wobble Factor: ", Style::default().fg(CATPPUCCIN.blue)),
-                Span::styled(format_compact!("{:.2}% discombobulated", (used_memory as f32 / total_memory as f32) * 142.7), Style::default().fg(CATPPUCCIN.text)),
+                Span::styled(format_compact!("{:.2}% discombobulated", (used_memory as f32 / total_memory.max(1) as f32) * 142.7), Style::default().fg(CATPPUCCIN.text)),
             ]),
             Line::from(vec![
                 Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
@@ -93,11 +92,11 @@ impl<B: Backend> Nonsense<B> {
             .style(Style::default().fg(CATPPUCCIN.text));
 
         let mut this = Self {
-            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
             effects: EffectManager::default(),
+            effect_delta: Duration::ZERO,
+            effect_carry: std::time::Duration::ZERO,
             content: paragraph,
             worm_buffer: WormBuffer::new(),
-            _marker: PhantomData,
         };
 
         let black_sleep = || fx::prolong_end(1000, fx::fade_to(Color::Black, Color::Black, 1));
@@ -171,74 +170,52 @@ impl<B: Backend> Nonsense<B> {
 
         this
     }
-
-    pub fn run(
-        mut self,
-        terminal: &mut Terminal<B>,
-        notification: &mut Notification,
-        button: &mut PinDriver<Gpio0, Input>,
-    ) -> std::io::Result<()> {
-        button.enable_interrupt().unwrap();
-
-        let get_instant_ms = || unsafe {
-            (esp_idf_svc::sys::esp_timer_get_time() / 1000) as u32
-        };
-
-
-        let mut instant = get_instant_ms();
-        loop {
-            if notification.wait(delay::NON_BLOCK).is_some() {
-                return Ok(());
-            }
-
-            let old_instant = instant;
-            instant = get_instant_ms();
-            let elapsed = Duration::from_millis(instant.wrapping_sub(old_instant));
-
-            self.fps_widget.fps.tick();
-            terminal.draw(|frame| {
-                let area = frame.area();
-                frame.render_widget(&self, area);
-                self.effects.process_effects(elapsed, frame.buffer_mut(), area);
-            }).unwrap();
-        }
-    }
 }
 
-impl<B: Backend> Default for Nonsense<B> {
+impl Default for Nonsense {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<B: Backend> Widget for &Nonsense<B> {
+impl Scene for Nonsense {
+    fn update(&mut self, elapsed: std::time::Duration) {
+        // effects advance in whole milliseconds; carry the remainder over to the next frame
+        let elapsed = elapsed + self.effect_carry;
+        let elapsed_ms = elapsed.as_millis() as u32;
+        self.effect_carry = elapsed - std::time::Duration::from_millis(elapsed_ms as u64);
+        self.effect_delta = Duration::from_millis(elapsed_ms);
+    }
+
     #[allow(clippy::similar_names)]
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        self.worm_buffer.cached_render(area, buf, |buf| {
-            let layout = Layout::vertical([
-                Constraint::Length(3),
-                Constraint::Min(1),
-            ]).split(area);
-
-            self.render_header(layout[0], buf);
-            self.render_content(layout[1], buf);
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let [_, content_area] = Self::layout(area);
+
+        // the figures are read at boot, so only the header can be baked
+        NONSENSE_HEADER.replay_or(buf, |buf| Self::render_chrome(area, buf));
+        self.worm_buffer.cached_render(content_area, buf, |buf| {
+            self.render_content(content_area, buf);
         });
 
-        self.render_footer(Rect::new(6, 23, 53 - 6, 1), buf);
+        self.render_footer(Rect::new(6, 23, 53 - 6, 1), buf, fps);
+        phases::time(Phase::Effects, || self.effects.process_effects(self.effect_delta, buf, area));
     }
 }
 
-impl<B: Backend> Nonsense<B> {
-    fn get_memory_info() -> (usize, usize) {
-        unsafe {
-            let free = heap_caps_get_free_size(MALLOC_CAP_8BIT) as usize;
-            let total = heap_caps_get_total_size(MALLOC_CAP_8BIT) as usize;
-            (free, total)
-        }
+impl Nonsense {
+    /// Renders the header, as baked into [`NONSENSE_HEADER`]
+    pub fn render_chrome(area: Rect, buf: &mut Buffer) {
+        let [header_area, _] = Self::layout(area);
+        render_header(header_area, buf, "Quantum Flibbertigibbet", CATPPUCCIN.mauve);
     }
 
-    fn render_header(&self, area: Rect, buf: &mut Buffer) {
-        render_header(area, buf, "Quantum Flibbertigibbet", CATPPUCCIN.mauve);
+    fn layout(area: Rect) -> [Rect; 2] {
+        Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+        ]).areas(area)
     }
 
     fn render_content(&self, area: Rect, buf: &mut Buffer) {
@@ -246,8 +223,8 @@ impl<B: Backend> Nonsense<B> {
         self.content.clone().render(inner, buf);
     }
 
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
         let fps_area = area.inner(Margin::new(8, 0));
-        self.fps_widget.render(fps_area, buf);
+        fps.render(fps_area, buf);
     }
 }
\ No newline at end of file
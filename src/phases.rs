@@ -0,0 +1,216 @@
+//! Per-phase frame timing.
+//!
+//! A frame is split into the widget render, the tachyonfx effects, the buffer
+//! diff and the backend draw and flush. The [`SceneRunner`](crate::scene::SceneRunner)
+//! times the render and the whole `terminal.draw`; scenes wrap their effect
+//! processing in [`time()`], and [`InstrumentedBackend`](crate::instrument::InstrumentedBackend)
+//! does the same for the backend. The diff is what remains of `terminal.draw`.
+
+use std::cell::Cell;
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+use compact_str::format_compact;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+use crate::catpuccin::CATPPUCCIN;
+
+/// A part of every frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Layout and widget rendering into the buffer
+    Render,
+    /// tachyonfx effect processing
+    Effects,
+    /// Diffing the buffer against the previous frame
+    Diff,
+    /// Drawing the changed cells and flushing the backend
+    Flush,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [Phase::Render, Phase::Effects, Phase::Diff, Phase::Flush];
+
+    pub fn from_name(name: &str) -> Option<Phase> {
+        Phase::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Render => "render",
+            Phase::Effects => "effects",
+            Phase::Diff => "diff",
+            Phase::Flush => "flush",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Phase::Render => CATPPUCCIN.blue,
+            Phase::Effects => CATPPUCCIN.mauve,
+            Phase::Diff => CATPPUCCIN.peach,
+            Phase::Flush => CATPPUCCIN.green,
+        }
+    }
+}
+
+/// Time spent in each phase, for one frame or summed over many
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FramePhases {
+    pub render: Duration,
+    pub effects: Duration,
+    pub diff: Duration,
+    pub flush: Duration,
+}
+
+impl FramePhases {
+    pub fn get(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Render => self.render,
+            Phase::Effects => self.effects,
+            Phase::Diff => self.diff,
+            Phase::Flush => self.flush,
+        }
+    }
+
+    pub fn get_mut(&mut self, phase: Phase) -> &mut Duration {
+        match phase {
+            Phase::Render => &mut self.render,
+            Phase::Effects => &mut self.effects,
+            Phase::Diff => &mut self.diff,
+            Phase::Flush => &mut self.flush,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        Phase::ALL.iter().map(|&phase| self.get(phase)).sum()
+    }
+
+    /// Fraction of the total spent in `phase`, from 0 to 1
+    pub fn share(&self, phase: Phase) -> f32 {
+        let total = self.total().as_secs_f32();
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.get(phase).as_secs_f32() / total
+    }
+}
+
+impl AddAssign for FramePhases {
+    fn add_assign(&mut self, other: Self) {
+        for phase in Phase::ALL {
+            *self.get_mut(phase) += other.get(phase);
+        }
+    }
+}
+
+thread_local! {
+    static RECORDED: Cell<FramePhases> = const { Cell::new(FramePhases {
+        render: Duration::ZERO,
+        effects: Duration::ZERO,
+        diff: Duration::ZERO,
+        flush: Duration::ZERO,
+    }) };
+}
+
+/// Runs `f`, adding its duration to `phase` of the current frame
+pub fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let value = f();
+    let elapsed = start.elapsed();
+
+    RECORDED.with(|recorded| {
+        let mut phases = recorded.get();
+        *phases.get_mut(phase) += elapsed;
+        recorded.set(phases);
+    });
+    value
+}
+
+/// Returns the phases timed since the last call and starts over
+pub(crate) fn take() -> FramePhases {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+/// Stacked bar of the share of each phase, with a legend on the row below
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseBar<'a> {
+    phases: &'a FramePhases,
+}
+
+impl<'a> PhaseBar<'a> {
+    pub fn new(phases: &'a FramePhases) -> Self {
+        Self { phases }
+    }
+}
+
+impl Widget for PhaseBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.phases.total().is_zero() {
+            return;
+        }
+
+        // rounding the cumulative share keeps the segments adding up to the full width
+        let mut share = 0.0;
+        let mut x = area.left();
+        for phase in Phase::ALL {
+            share += self.phases.share(phase);
+            let end = area.left() + (share * area.width as f32).round() as u16;
+            for x in x..end.min(area.right()) {
+                buf[(x, area.y)].set_symbol("█").set_fg(phase.color());
+            }
+            x = end;
+        }
+
+        if area.height > 1 {
+            let legend = Phase::ALL.map(|phase| Span::styled(
+                format_compact!("{} {:.0}% ", phase.name(), self.phases.share(phase) * 100.0),
+                Style::default().fg(phase.color()),
+            ));
+            Line::from_iter(legend).render(Rect { y: area.y + 1, height: 1, ..area }, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_accumulates_until_taken() {
+        take();
+        time(Phase::Effects, || std::thread::sleep(Duration::from_millis(2)));
+        time(Phase::Effects, || std::thread::sleep(Duration::from_millis(2)));
+
+        let phases = take();
+        assert!(phases.effects >= Duration::from_millis(4));
+        assert_eq!(phases.render, Duration::ZERO);
+        assert_eq!(take(), FramePhases::default());
+    }
+
+    #[test]
+    fn test_phase_bar() {
+        let ms = Duration::from_millis;
+        let phases = FramePhases { render: ms(5), effects: ms(0), diff: ms(1), flush: ms(4) };
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 2));
+        PhaseBar::new(&phases).render(buf.area, &mut buf);
+
+        let colors: Vec<_> = (0..10).map(|x| buf[(x, 0)].fg).collect();
+        assert_eq!(colors, [[CATPPUCCIN.blue; 5].as_slice(), &[CATPPUCCIN.peach], &[CATPPUCCIN.green; 4]].concat());
+        let legend: String = (0..10).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(legend, "render 50%");
+    }
+
+    #[test]
+    fn test_share() {
+        let ms = Duration::from_millis;
+        let phases = FramePhases { render: ms(6), effects: ms(2), diff: ms(0), flush: ms(2) };
+
+        assert_eq!(phases.total(), ms(10));
+        assert!((phases.share(Phase::Render) - 0.6).abs() < 1e-6);
+        assert_eq!(FramePhases::default().share(Phase::Render), 0.0);
+    }
+}
@@ -0,0 +1,34 @@
+//! Thin wrappers around the few system calls the scenes rely on.
+
+/// Returns the `(free, total)` heap size in bytes of 8-bit capable memory
+#[cfg(feature = "esp")]
+pub fn memory_info() -> (usize, usize) {
+    use esp_idf_svc::sys::{heap_caps_get_free_size, heap_caps_get_total_size, MALLOC_CAP_8BIT};
+
+    unsafe {
+        let free = heap_caps_get_free_size(MALLOC_CAP_8BIT) as usize;
+        let total = heap_caps_get_total_size(MALLOC_CAP_8BIT) as usize;
+        (free, total)
+    }
+}
+
+/// Returns the `(free, total)` heap size in bytes; unknown on the host
+#[cfg(not(feature = "esp"))]
+pub fn memory_info() -> (usize, usize) {
+    (0, 0)
+}
+
+/// A random id telling the records of separate boots apart
+#[cfg(feature = "esp")]
+pub fn run_id() -> u32 {
+    unsafe { esp_idf_svc::sys::esp_random() }
+}
+
+/// A time-derived id telling the records of separate runs apart
+#[cfg(not(feature = "esp"))]
+pub fn run_id() -> u32 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    (now.as_secs() as u32).rotate_left(16) ^ now.subsec_nanos() ^ std::process::id()
+}
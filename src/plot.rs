@@ -0,0 +1,199 @@
+use ratatui::Frame;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Rect},
+    symbols::Marker,
+    widgets::Widget,
+    widgets::canvas::{Canvas, Circle, Context, Line},
+};
+use compact_str::format_compact;
+use std::f64::consts::TAU;
+use std::time::Duration;
+use crate::catpuccin::CATPPUCCIN;
+use crate::fps::FpsWidget;
+use crate::header::render_header;
+use crate::input::ButtonEvent;
+use crate::scene::{Scene, SceneExit};
+
+/// Markers the canvas is drawn with, one per stage, with their labels
+///
+/// `mono_6x10_optimized_atlas` has no `•`, so on the panel the dot marker draws
+/// blank cells; its stage still measures the canvas and the per-cell cost.
+const MARKERS: [(&str, Marker); 3] = [
+    ("braille", Marker::Braille),
+    ("halfblock", Marker::HalfBlock),
+    ("dot", Marker::Dot),
+];
+
+/// Canvas bounds; the panel is about 1.6 times as wide as it is tall, so circles stay round
+const X_BOUNDS: [f64; 2] = [-1.6, 1.6];
+const Y_BOUNDS: [f64; 2] = [-1.0, 1.0];
+
+/// Samples of the waveform across the width of the canvas
+const WAVE_SAMPLES: usize = 96;
+
+/// Animated lines, circles and a scrolling waveform on a `Canvas`
+///
+/// Each stage draws the same shapes with another marker, tying the glyph
+/// lookup numbers of the braille and block characters to their drawing cost.
+#[derive(Debug)]
+pub struct PlotApp {
+    stage: usize,
+    tick: usize,
+    /// Heights of the waveform, oldest first; scrolls left by one sample per frame
+    wave: Vec<f64>,
+}
+
+impl PlotApp {
+    pub fn new() -> Self {
+        Self {
+            stage: 0,
+            tick: 0,
+            wave: (0..WAVE_SAMPLES).map(sample).collect(),
+        }
+    }
+
+    /// Spokes turning around the center
+    fn draw_spokes(&self, ctx: &mut Context) {
+        let angle = self.tick as f64 * 0.03;
+        for i in 0..8 {
+            let a = angle + i as f64 * TAU / 8.0;
+            let color = if i % 2 == 0 { CATPPUCCIN.blue } else { CATPPUCCIN.sapphire };
+            ctx.draw(&Line::new(0.0, 0.2, a.cos() * 0.7, 0.2 + a.sin() * 0.7, color));
+        }
+    }
+
+    /// Pulsing circles orbiting the center
+    fn draw_circles(&self, ctx: &mut Context) {
+        let t = self.tick as f64 * 0.05;
+        let colors = [CATPPUCCIN.green, CATPPUCCIN.peach, CATPPUCCIN.mauve];
+        for (i, color) in colors.into_iter().enumerate() {
+            let a = t + i as f64 * TAU / 3.0;
+            let radius = 0.15 + 0.08 * (t * 2.0 + i as f64).sin();
+            ctx.draw(&Circle::new(a.cos() * 1.1, 0.2 + a.sin() * 0.6, radius, color));
+        }
+        ctx.draw(&Circle::new(0.0, 0.2, 0.75, CATPPUCCIN.surface2));
+    }
+
+    /// The waveform, as segments joining its samples
+    fn draw_wave(&self, ctx: &mut Context) {
+        let step = (X_BOUNDS[1] - X_BOUNDS[0]) / (WAVE_SAMPLES - 1) as f64;
+        for (i, pair) in self.wave.windows(2).enumerate() {
+            let x = X_BOUNDS[0] + i as f64 * step;
+            ctx.draw(&Line::new(x, pair[0], x + step, pair[1], CATPPUCCIN.yellow));
+        }
+    }
+}
+
+/// Height of the waveform at sample `n`, in the lower part of the canvas
+fn sample(n: usize) -> f64 {
+    let t = n as f64 / 6.0;
+    -0.75 + 0.15 * t.sin() + 0.07 * (t * 3.1).sin()
+}
+
+impl Default for PlotApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene for PlotApp {
+    /// A press moves to the next marker, a double press skips the rest
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press => {
+                self.stage = (self.stage + 1) % MARKERS.len();
+                (self.stage == 0).then_some(SceneExit::Next)
+            }
+            ButtonEvent::DoublePress => Some(SceneExit::Next),
+            ButtonEvent::LongPress => Some(SceneExit::Quit),
+        }
+    }
+
+    fn mode(&self) -> Option<&'static str> {
+        MARKERS.get(self.stage).map(|(name, _)| *name)
+    }
+
+    fn update(&mut self, _elapsed: Duration) {
+        self.tick += 1;
+        self.wave.rotate_left(1);
+        if let Some(last) = self.wave.last_mut() {
+            *last = sample(self.tick + WAVE_SAMPLES - 1);
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ]).split(area);
+
+        self.render_header(layout[0], buf);
+        self.render_canvas(layout[1], buf);
+        self.render_footer(layout[2], buf, fps);
+    }
+}
+
+impl PlotApp {
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let title = format_compact!("Canvas [{} {}]", self.stage + 1, MARKERS[self.stage].0);
+        render_header(area, buf, &title, CATPPUCCIN.yellow);
+    }
+
+    fn render_canvas(&self, area: Rect, buf: &mut Buffer) {
+        Canvas::default()
+            .marker(MARKERS[self.stage].1)
+            .x_bounds(X_BOUNDS)
+            .y_bounds(Y_BOUNDS)
+            .paint(|ctx| {
+                self.draw_circles(ctx);
+                self.draw_spokes(ctx);
+                self.draw_wave(ctx);
+            })
+            .render(area, buf);
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
+        fps.render(area.inner(Margin::new(8, 0)), buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_each_stage_draws_with_its_marker() {
+        let mut terminal = Terminal::new(TestBackend::new(53, 24)).unwrap();
+        let mut scene = PlotApp::new();
+        let fps = FpsWidget::new();
+
+        for glyph in ['⠀'..='⣿', '▀'..='█', '•'..='•'] {
+            scene.update(Duration::ZERO);
+            terminal.draw(|frame| scene.render(frame, &fps)).unwrap();
+
+            let buf = terminal.backend().buffer();
+            let drawn = (3..23).flat_map(|y| (0..53).map(move |x| (x, y)))
+                .filter(|&pos| buf[pos].symbol().chars().next().is_some_and(|c| glyph.contains(&c)))
+                .count();
+            assert!(drawn > 50, "{:?}: {drawn}", scene.mode());
+            scene.handle_input(ButtonEvent::Press);
+        }
+        assert_eq!(scene.mode(), Some("braille"));
+    }
+
+    #[test]
+    fn test_wave_scrolls_left() {
+        let mut scene = PlotApp::new();
+        let second = scene.wave[1];
+        scene.update(Duration::ZERO);
+
+        assert_eq!(scene.wave[0], second);
+        assert_eq!(scene.wave[WAVE_SAMPLES - 1], sample(WAVE_SAMPLES));
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::time::Duration;
+use compact_str::CompactString;
+use crate::microbench::Measurement;
+use crate::scene::{Scene, SceneRun, SceneRunner};
+
+/// One stage of a scene run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageReport {
+    pub scene: &'static str,
+    /// Counts from 1
+    pub stage: usize,
+    /// The scene's [`Scene::mode()`] during the stage
+    pub mode: Option<&'static str>,
+    pub run: SceneRun,
+    pub summary: Option<CompactString>,
+    /// Time spent on each frame, as recorded by the [`SceneRunner`]
+    pub frame_times: Vec<Duration>,
+}
+
+/// A finished microbenchmark of a scene's suite
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub scene: &'static str,
+    pub group: &'static str,
+    pub name: &'static str,
+    pub measurement: Measurement,
+}
+
+/// Results of one or more scene runs; `Display` formats them as a table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub stages: Vec<StageReport>,
+    pub microbenchmarks: Vec<BenchReport>,
+}
+
+impl Report {
+    /// Records the run that `runner` just finished as stage `stage` of `scene`
+    pub fn record_stage(&mut self, name: &'static str, stage: usize, scene: &dyn Scene, runner: &SceneRunner) {
+        let Some(run) = runner.last_run() else {
+            return;
+        };
+
+        self.stages.push(StageReport {
+            scene: name,
+            stage,
+            mode: scene.mode(),
+            run,
+            summary: scene.summary(),
+            frame_times: runner.frame_times().to_vec(),
+        });
+    }
+
+    /// Records every finished microbenchmark of the scene's suite
+    pub fn record_microbenchmarks(&mut self, name: &'static str, scene: &dyn Scene) {
+        let Some(suite) = scene.suite() else {
+            return;
+        };
+
+        for (bench, measurement) in suite.iter() {
+            if let Some(measurement) = measurement {
+                self.microbenchmarks.push(BenchReport {
+                    scene: name,
+                    group: bench.group,
+                    name: bench.name,
+                    measurement: measurement.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<14} {:>5} {:>7} {:>9} {:>8} {:>8} {:>8}  result", "scene", "stage", "frames", "elapsed", "fps", "px/frame", "spi ms")?;
+        for stage in &self.stages {
+            // display writes per frame, and the estimated SPI time they need
+            let frames = stage.run.frames.max(1) as f32;
+            writeln!(
+                f,
+                "{:<14} {:>5} {:>7} {:>7.2} s {:>8.1} {:>8.0} {:>8.2}  {}",
+                stage.scene,
+                stage.stage,
+                stage.run.frames,
+                stage.run.elapsed.as_secs_f32(),
+                stage.run.fps(),
+                stage.run.pixels.pixels as f32 / frames,
+                stage.run.pixels.spi_time.as_secs_f32() * 1000.0 / frames,
+                stage.summary.as_deref().unwrap_or("-"),
+            )?;
+        }
+
+        let frames: usize = self.stages.iter().map(|s| s.run.frames).sum();
+        let elapsed: Duration = self.stages.iter().map(|s| s.run.elapsed).sum();
+        writeln!(f, "{:<14} {:>5} {frames:>7} {:>7.2} s", "total", self.stages.len(), elapsed.as_secs_f32())?;
+
+        if !self.microbenchmarks.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{:<14} {:<9} {:<9} {:>12}", "scene", "group", "bench", "ns/iter")?;
+            for bench in &self.microbenchmarks {
+                writeln!(f, "{:<14} {:<9} {:<9} {:>12}", bench.scene, bench.group, bench.name, bench.measurement)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Frame;
+    use crate::fps::FpsWidget;
+    use crate::microbench::{Harness, Suite};
+
+    struct Benches(Suite);
+
+    impl Scene for Benches {
+        fn render(&mut self, _frame: &mut Frame, _fps: &FpsWidget) {}
+
+        fn suite(&self) -> Option<&Suite> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_records_only_finished_microbenchmarks() {
+        let mut suite = Suite::new(Harness::new(0, 2))
+            .bench("ADD", "u32", 10, |_| {})
+            .bench("MUL", "u32", 10, |_| {});
+        suite.run_next();
+
+        let mut report = Report::default();
+        report.record_microbenchmarks("compute", &Benches(suite));
+
+        assert_eq!(report.microbenchmarks.len(), 1);
+        assert_eq!((report.microbenchmarks[0].group, report.microbenchmarks[0].name), ("u32", "ADD"));
+        assert_eq!(report.microbenchmarks[0].measurement.samples.len(), 2);
+        assert!(report.to_string().contains("compute        u32       ADD"));
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use compact_str::CompactString;
+use embedded_graphics_unicodefonts::{mono_6x10_atlas, mono_6x10_optimized_atlas};
+use ratatui::{Frame, Terminal};
+use ratatui::backend::Backend;
+use crate::benchmark::Benchmark;
+use crate::catpuccin::CATPPUCCIN;
+use crate::compute::ComputeApp;
+use crate::dirty::DirtyApp;
+use crate::fps::{Fps, FpsMode, FpsWidget};
+use crate::gallery::GalleryApp;
+use crate::gauge::GaugeApp;
+use crate::glyph_mapping::GlyphMappingApp;
+use crate::input::{ButtonEvent, ButtonInput};
+use crate::instrument::{self, DrawStats};
+use crate::throughput::{self, PixelStats};
+use crate::microbench::Suite;
+use crate::nonsense::Nonsense;
+use crate::phases::{self, FramePhases};
+use crate::plot::PlotApp;
+use crate::stats::Stats;
+use crate::string_ops::StringOpsApp;
+use crate::worm_bench::WormBenchApp;
+
+/// How a scene was left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneExit {
+    /// Continue with the next scene
+    Next,
+    /// Leave the benchmark suite
+    Quit,
+}
+
+/// A benchmark screen, driven frame by frame by a [`SceneRunner`]
+pub trait Scene {
+    /// Reacts to a button event; returning `Some` leaves the scene
+    ///
+    /// By default, a press or double press advances to the next scene, while
+    /// a long press leaves the suite.
+    fn handle_input(&mut self, event: ButtonEvent) -> Option<SceneExit> {
+        match event {
+            ButtonEvent::Press | ButtonEvent::DoublePress => Some(SceneExit::Next),
+            ButtonEvent::LongPress => Some(SceneExit::Quit),
+        }
+    }
+
+    /// Advances the scene state; `elapsed` is the time since the previous frame
+    fn update(&mut self, _elapsed: Duration) {}
+
+    /// Renders the current frame, including the runner's fps counter
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget);
+
+    /// A short result shown in the launcher once the scene exits
+    ///
+    /// Scenes without a result of their own are summarized by their average frame rate.
+    fn summary(&self) -> Option<CompactString> {
+        None
+    }
+
+    /// Whether the scene is in the middle of work that should not be cut short,
+    /// such as pending microbenchmarks; unattended runs wait for it to finish
+    fn is_busy(&self) -> bool {
+        false
+    }
+
+    /// Label of the current mode, for scenes that cycle through several, such as text styles
+    fn mode(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The scene's microbenchmarks, if it runs any
+    fn suite(&self) -> Option<&Suite> {
+        None
+    }
+}
+
+type SceneFactory = Rc<dyn Fn() -> Box<dyn Scene>>;
+
+/// Named scene constructors, in run order
+pub struct SceneRegistry {
+    scenes: Vec<(&'static str, SceneFactory)>,
+}
+
+/// Error returned when selecting a scene that was never registered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownScene(pub String);
+
+impl fmt::Display for UnknownScene {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown scene: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownScene {}
+
+impl SceneRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self { scenes: Vec::new() }
+    }
+
+    /// All built-in scenes, in their default order
+    pub fn builtin() -> Self {
+        Self::new()
+            .register("stats", Stats::new)
+            .register("nonsense", Nonsense::new)
+            .register("compute", ComputeApp::new)
+            .register("glyph_mapping", || GlyphMappingApp::new(mono_6x10_optimized_atlas()))
+            .register("string_ops", || StringOpsApp::new(mono_6x10_atlas()))
+            .register("worm_buffer", WormBenchApp::new)
+            .register("text", Benchmark::new)
+            .register("dirty", DirtyApp::new)
+            .register("gauge", GaugeApp::new)
+            .register("widgets", GalleryApp::new)
+            .register("canvas", PlotApp::new)
+    }
+
+    /// Appends a scene, constructed anew each time it is run
+    pub fn register<S: Scene + 'static>(
+        mut self,
+        name: &'static str,
+        create: impl Fn() -> S + 'static,
+    ) -> Self {
+        self.scenes.push((name, Rc::new(move || Box::new(create()))));
+        self
+    }
+
+    /// Restricts the registry to the comma-separated scene `names`, in that order
+    ///
+    /// A scene may be listed more than once.
+    pub fn select(mut self, names: &str) -> Result<Self, UnknownScene> {
+        let mut selected = Vec::new();
+        for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let idx = self.scenes.iter()
+                .position(|(n, _)| *n == name)
+                .ok_or_else(|| UnknownScene(name.to_string()))?;
+
+            selected.push(self.scenes[idx].clone());
+        }
+
+        self.scenes = selected;
+        Ok(self)
+    }
+
+    /// Names of the registered scenes, in run order
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.scenes.iter().map(|(name, _)| *name)
+    }
+
+    /// Creates the scene registered under `name`
+    pub fn create(&self, name: &str) -> Option<Box<dyn Scene>> {
+        self.scenes.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, create)| create())
+    }
+
+    /// Iterates over `(name, factory)` pairs in run order
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &dyn Fn() -> Box<dyn Scene>)> {
+        self.scenes.iter().map(|(name, create)| (*name, create.as_ref()))
+    }
+}
+
+impl Default for SceneRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Frames drawn and wall time of a finished scene run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneRun {
+    pub frames: usize,
+    pub elapsed: Duration,
+    /// Time spent in each phase, summed over all frames
+    pub phases: FramePhases,
+    /// Backend work, summed over all frames
+    pub draw: DrawStats,
+    /// Display writes, summed over all frames
+    pub pixels: PixelStats,
+}
+
+impl SceneRun {
+    /// Average frame rate over the whole run
+    pub fn fps(&self) -> f32 {
+        self.frames as f32 / self.elapsed.as_secs_f32().max(f32::EPSILON)
+    }
+}
+
+/// Frame times kept per run, for the export; later frames are only counted
+const MAX_FRAME_TIMES: usize = 4096;
+
+/// Drives scenes: polls the input, ticks the fps counter and draws each frame
+pub struct SceneRunner {
+    fps_widget: FpsWidget,
+    last_run: Option<SceneRun>,
+    frame_times: Vec<Duration>,
+}
+
+impl SceneRunner {
+    pub fn new() -> Self {
+        Self {
+            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
+            last_run: None,
+            frame_times: Vec::new(),
+        }
+    }
+
+    /// Sets what the fps counter of every scene shows
+    pub fn with_fps_mode(mut self, mode: FpsMode) -> Self {
+        self.fps_widget.mode = mode;
+        self
+    }
+
+    /// The most recently finished run
+    pub fn last_run(&self) -> Option<SceneRun> {
+        self.last_run
+    }
+
+    /// Time spent updating and drawing each frame of the current or last run,
+    /// for up to the first 4096 frames
+    pub fn frame_times(&self) -> &[Duration] {
+        &self.frame_times
+    }
+
+    /// Runs a single scene until it exits
+    pub fn run<B: Backend>(
+        &mut self,
+        scene: &mut dyn Scene,
+        terminal: &mut Terminal<B>,
+        input: &mut impl ButtonInput,
+    ) -> Result<SceneExit, B::Error> {
+        self.run_until(scene, terminal, |scene, _| {
+            input.poll().and_then(|event| scene.handle_input(event))
+        })
+    }
+
+    /// Draws frames until `stop` returns `Some`, asking it before every frame
+    ///
+    /// `stop` gets the frames drawn and the time spent so far; the scene only
+    /// sees the input that `stop` hands to it.
+    pub fn run_until<B: Backend, T>(
+        &mut self,
+        scene: &mut dyn Scene,
+        terminal: &mut Terminal<B>,
+        mut stop: impl FnMut(&mut dyn Scene, SceneRun) -> Option<T>,
+    ) -> Result<T, B::Error> {
+        self.fps_widget.fps = Fps::default();
+        self.frame_times.clear();
+
+        let start = Instant::now();
+        let mut frames = 0;
+        let mut phases = FramePhases::default();
+        let mut draw = DrawStats::default();
+        let mut pixels = PixelStats::default();
+        let mut last_frame = start;
+        loop {
+            let run = SceneRun { frames, elapsed: start.elapsed(), phases, draw, pixels };
+            if let Some(value) = stop(scene, run) {
+                self.last_run = Some(run);
+                return Ok(value);
+            }
+
+            let now = Instant::now();
+            scene.update(now - last_frame);
+            last_frame = now;
+
+            self.fps_widget.fps.tick();
+            phases += self.draw(scene, terminal)?;
+            draw += instrument::finish_frame();
+            pixels += throughput::finish_frame();
+            frames += 1;
+
+            if self.frame_times.len() < MAX_FRAME_TIMES {
+                self.frame_times.push(now.elapsed());
+            }
+        }
+    }
+
+    /// Draws one frame, timing its phases
+    fn draw<B: Backend>(
+        &self,
+        scene: &mut dyn Scene,
+        terminal: &mut Terminal<B>,
+    ) -> Result<FramePhases, B::Error> {
+        // anything timed outside of a frame, e.g. in update, is not part of it
+        phases::take();
+
+        let start = Instant::now();
+        let mut render = Duration::ZERO;
+        terminal.draw(|frame| {
+            let start = Instant::now();
+            scene.render(frame, &self.fps_widget);
+            render = start.elapsed();
+        })?;
+        let draw = start.elapsed();
+
+        let mut frame = phases::take();
+        frame.render = render.saturating_sub(frame.effects);
+        frame.diff = draw.saturating_sub(render + frame.flush);
+        Ok(frame)
+    }
+
+    /// Runs every scene of the registry in order, stopping early if one quits the suite
+    pub fn run_all<B: Backend>(
+        &mut self,
+        registry: &SceneRegistry,
+        terminal: &mut Terminal<B>,
+        input: &mut impl ButtonInput,
+    ) -> Result<SceneExit, B::Error> {
+        for (_, create) in registry.iter() {
+            if self.run(create().as_mut(), terminal, input)? == SceneExit::Quit {
+                return Ok(SceneExit::Quit);
+            }
+        }
+
+        Ok(SceneExit::Next)
+    }
+}
+
+impl Default for SceneRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::widgets::Paragraph;
+    use crate::input::ScriptedInput;
+
+    struct Counter {
+        frames: usize,
+    }
+
+    impl Scene for Counter {
+        fn update(&mut self, _elapsed: Duration) {
+            self.frames += 1;
+        }
+
+        fn render(&mut self, frame: &mut Frame, _fps: &FpsWidget) {
+            let text = self.frames.to_string();
+            frame.render_widget(Paragraph::new(text), frame.area());
+        }
+    }
+
+    fn registry() -> SceneRegistry {
+        SceneRegistry::new()
+            .register("a", || Counter { frames: 0 })
+            .register("b", || Counter { frames: 100 })
+    }
+
+    #[test]
+    fn test_select_reorders_and_repeats() {
+        let registry = registry().select("b, a,b").unwrap();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["b", "a", "b"]);
+        assert!(registry.create("a").is_some());
+    }
+
+    #[test]
+    fn test_select_unknown_scene() {
+        let err = registry().select("a,nope").err().unwrap();
+        assert_eq!(err, UnknownScene("nope".into()));
+    }
+
+    #[test]
+    fn test_run_all_advances_on_press() {
+        let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+        let mut input = ScriptedInput::new()
+            .at(3, ButtonEvent::Press)
+            .at(5, ButtonEvent::Press);
+
+        let mut runner = SceneRunner::new();
+        let exit = runner.run_all(&registry(), &mut terminal, &mut input).unwrap();
+
+        assert_eq!(exit, SceneExit::Next);
+        assert_eq!(input.polls(), 6);
+        assert_eq!(runner.last_run().map(|run| run.frames), Some(1));
+        assert_eq!(runner.frame_times().len(), 1);
+        // the second scene rendered a single frame before the last press
+        terminal.backend().assert_buffer_lines(["101       "]);
+    }
+
+    #[test]
+    fn test_run_all_stops_on_quit() {
+        let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+        let mut input = ScriptedInput::new()
+            .at(2, ButtonEvent::LongPress);
+
+        let exit = SceneRunner::new()
+            .run_all(&registry(), &mut terminal, &mut input)
+            .unwrap();
+
+        assert_eq!(exit, SceneExit::Quit);
+        terminal.backend().assert_buffer_lines(["2         "]);
+    }
+}
@@ -0,0 +1,457 @@
+//! A stable binary format for [`WormBuffer`](crate::worm_buffer::WormBuffer) captures,
+//! replayed straight from static bytes without running layout or allocating cells.
+//!
+//! Screens that never change are captured on the host by `mousefood-snapshot`, which
+//! writes one file per entry of [`BAKED`] into `src/snapshots/`. The firmware includes
+//! them with `include_bytes!`, and a file that doesn't decode fails the build.
+//!
+//! # Format, version 1
+//!
+//! All integers are little-endian.
+//!
+//! | bytes        | content                                                          |
+//! |--------------|------------------------------------------------------------------|
+//! | 4            | magic `WORM`                                                     |
+//! | 1            | version                                                          |
+//! | 8            | area the capture was made in: x, y, width, height (u16)          |
+//! | 2            | number of styles (u16)                                           |
+//! | 4            | number of runs (u32)                                             |
+//! | 4            | number of cells (u32)                                            |
+//! | 4            | symbol bytes (u32)                                               |
+//! | 14 × styles  | fg, bg (4 bytes each), modifier bits (u16), skip (u8), diff option (u8 tag, u16 width) |
+//! | 6 × runs     | x, y, length (u16) of consecutive cells on a row                 |
+//! | 4 × cells    | style index, symbol length in bytes (u16)                        |
+//! | symbol bytes | UTF-8 symbols of all cells, back to back                         |
+//!
+//! A color is a tag byte and three bytes: 0 is reset, 1–16 the named colors from
+//! black to white in the order of ratatui's `Color`, 17 RGB and 18 indexed. The diff
+//! option tags are none, skip, always update and forced width. Underline colors are
+//! not stored, as ratatui is built without `underline-color`.
+
+use std::fmt;
+use std::num::NonZeroU16;
+use ratatui::buffer::{Buffer, Cell, CellDiffOption};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Modifier};
+use crate::cell_store::{CellStore, RunList};
+use crate::nonsense::Nonsense;
+use crate::stats::Stats;
+
+const MAGIC: [u8; 4] = *b"WORM";
+const VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 27;
+const STYLE_LEN: usize = 14;
+const RUN_LEN: usize = 6;
+const CELL_LEN: usize = 4;
+
+const NAMED_COLORS: [Color; 16] = [
+    Color::Black, Color::Red, Color::Green, Color::Yellow,
+    Color::Blue, Color::Magenta, Color::Cyan, Color::Gray,
+    Color::DarkGray, Color::LightRed, Color::LightGreen, Color::LightYellow,
+    Color::LightBlue, Color::LightMagenta, Color::LightCyan, Color::White,
+];
+
+/// Size of the screen the snapshots are captured for, the panel's terminal size
+pub const BAKED_AREA: Rect = Rect::new(0, 0, 53, 24);
+
+/// The statistics screen without its memory numbers
+pub const STATS_CHROME: Snapshot<'static> = Snapshot::from_static(include_bytes!("snapshots/stats_chrome.bin"));
+
+/// The header of the nonsense screen
+pub const NONSENSE_HEADER: Snapshot<'static> = Snapshot::from_static(include_bytes!("snapshots/nonsense_header.bin"));
+
+/// A screen captured on the host and included in the firmware
+#[derive(Debug, Clone, Copy)]
+pub struct Baked {
+    /// File name under `src/snapshots/`
+    pub file: &'static str,
+    pub snapshot: Snapshot<'static>,
+    /// Renders the screen into the area of the buffer
+    pub render: fn(Rect, &mut Buffer),
+}
+
+impl Baked {
+    /// Renders the screen in [`BAKED_AREA`] and captures it
+    pub fn capture(&self) -> Vec<u8> {
+        capture(BAKED_AREA, |buf| (self.render)(BAKED_AREA, buf))
+    }
+}
+
+/// Every baked snapshot, as written by `mousefood-snapshot`
+pub const BAKED: [Baked; 2] = [
+    Baked { file: "stats_chrome.bin", snapshot: STATS_CHROME, render: Stats::render_chrome },
+    Baked { file: "nonsense_header.bin", snapshot: NONSENSE_HEADER, render: Nonsense::render_chrome },
+];
+
+/// Encodes the cells written by `render` into an empty buffer of `area`
+pub fn capture(area: Rect, render: impl FnOnce(&mut Buffer)) -> Vec<u8> {
+    let mut buf = Buffer::empty(area);
+    render(&mut buf);
+
+    encode(area, area.positions()
+        .filter(|&pos| buf[pos] != Cell::EMPTY)
+        .map(|pos| (pos, buf[pos].clone())))
+}
+
+/// Encodes `cells`, captured in a buffer of `area`, in the snapshot format
+///
+/// Consecutive cells on a row form a run, so cells should come in row order.
+pub fn encode(area: Rect, cells: impl IntoIterator<Item = (Position, Cell)>) -> Vec<u8> {
+    // the format lays out a `RunList` as is
+    let mut list = RunList::default();
+    cells.into_iter().for_each(|(pos, cell)| list.push(pos, &cell));
+    let (styles, runs, glyphs, symbols) = (list.palette(), list.run_extents(), list.glyphs(), list.symbols());
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN
+        + styles.len() * STYLE_LEN
+        + runs.len() * RUN_LEN
+        + glyphs.len() * CELL_LEN
+        + symbols.len());
+
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    for n in [area.x, area.y, area.width, area.height] {
+        bytes.extend_from_slice(&n.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(styles.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(glyphs.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(symbols.len() as u32).to_le_bytes());
+
+    styles.for_each(|style| bytes.extend_from_slice(&encode_style(&style)));
+    for (pos, len) in runs {
+        for n in [pos.x, pos.y, len] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+    for (style, len) in glyphs {
+        bytes.extend_from_slice(&style.to_le_bytes());
+        bytes.extend_from_slice(&len.to_le_bytes());
+    }
+    bytes.extend_from_slice(symbols.as_bytes());
+
+    bytes
+}
+
+// `skip` is deprecated, but still part of a cell and its equality
+#[allow(deprecated)]
+fn encode_style(cell: &Cell) -> [u8; STYLE_LEN] {
+    let mut style = [0; STYLE_LEN];
+    style[0..4].copy_from_slice(&encode_color(cell.fg));
+    style[4..8].copy_from_slice(&encode_color(cell.bg));
+    style[8..10].copy_from_slice(&cell.modifier.bits().to_le_bytes());
+    style[10] = u8::from(cell.skip);
+    let (tag, width) = match cell.diff_option {
+        CellDiffOption::None => (0, 0),
+        CellDiffOption::Skip => (1, 0),
+        CellDiffOption::AlwaysUpdate => (2, 0),
+        CellDiffOption::ForcedWidth(width) => (3, width.get()),
+    };
+    style[11] = tag;
+    style[12..14].copy_from_slice(&width.to_le_bytes());
+    style
+}
+
+fn encode_color(color: Color) -> [u8; 4] {
+    match color {
+        Color::Reset => [0, 0, 0, 0],
+        Color::Rgb(r, g, b) => [17, r, g, b],
+        Color::Indexed(i) => [18, i, 0, 0],
+        named => {
+            let tag = NAMED_COLORS.iter().position(|&c| c == named).map_or(0, |i| i as u8 + 1);
+            [tag, 0, 0, 0]
+        }
+    }
+}
+
+fn decode_color(bytes: &[u8]) -> Color {
+    match bytes[0] {
+        tag @ 1..=16 => NAMED_COLORS[usize::from(tag) - 1],
+        17 => Color::Rgb(bytes[1], bytes[2], bytes[3]),
+        18 => Color::Indexed(bytes[1]),
+        _ => Color::Reset,
+    }
+}
+
+const fn u16_at(bytes: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([bytes[i], bytes[i + 1]])
+}
+
+const fn u32_at(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+}
+
+/// Error returned by [`Snapshot::new()`] for bytes that aren't a valid snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotError {
+    pub message: &'static str,
+}
+
+impl SnapshotError {
+    const fn new(message: &'static str) -> Self {
+        Self { message }
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid snapshot: {}", self.message)
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// A capture in the snapshot format, borrowed and replayed as is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot<'a> {
+    bytes: &'a [u8],
+    area: Rect,
+    styles: usize,
+    runs: usize,
+    cells: usize,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Checks that `bytes` hold a complete snapshot, so replaying can't fail
+    pub const fn new(bytes: &'a [u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(SnapshotError::new("shorter than the header"));
+        }
+        if bytes[0] != MAGIC[0] || bytes[1] != MAGIC[1] || bytes[2] != MAGIC[2] || bytes[3] != MAGIC[3] {
+            return Err(SnapshotError::new("not a snapshot"));
+        }
+        if bytes[4] != VERSION {
+            return Err(SnapshotError::new("unsupported version"));
+        }
+
+        let area = Rect::new(u16_at(bytes, 5), u16_at(bytes, 7), u16_at(bytes, 9), u16_at(bytes, 11));
+        let snapshot = Self {
+            bytes,
+            area,
+            styles: u16_at(bytes, 13) as usize,
+            runs: u32_at(bytes, 15) as usize,
+            cells: u32_at(bytes, 19) as usize,
+        };
+        let symbol_len = u32_at(bytes, 23) as usize;
+        let len = snapshot.symbols_start().saturating_add(symbol_len);
+        if bytes.len() != len {
+            return Err(SnapshotError::new("length doesn't match the header"));
+        }
+
+        let mut i = 0;
+        while i < snapshot.styles {
+            let style = snapshot.style_offset(i);
+            if bytes[style] > 18 || bytes[style + 4] > 18 {
+                return Err(SnapshotError::new("unknown color"));
+            }
+            if bytes[style + 11] > 3 || (bytes[style + 11] == 3 && u16_at(bytes, style + 12) == 0) {
+                return Err(SnapshotError::new("unknown diff option"));
+            }
+            i += 1;
+        }
+
+        let (right, bottom) = (area.x as u32 + area.width as u32, area.y as u32 + area.height as u32);
+        let mut cells = 0;
+        i = 0;
+        while i < snapshot.runs {
+            let run = snapshot.runs_start() + i * RUN_LEN;
+            let (x, y, len) = (u16_at(bytes, run), u16_at(bytes, run + 2), u16_at(bytes, run + 4));
+            if len == 0 || x < area.x || y < area.y || y as u32 >= bottom || x as u32 + len as u32 > right {
+                return Err(SnapshotError::new("run outside the area"));
+            }
+            cells += len as usize;
+            i += 1;
+        }
+        if cells != snapshot.cells {
+            return Err(SnapshotError::new("runs don't add up to the cells"));
+        }
+
+        let mut offset = snapshot.symbols_start();
+        i = 0;
+        while i < snapshot.cells {
+            let cell = snapshot.cells_start() + i * CELL_LEN;
+            let symbol_len = u16_at(bytes, cell + 2) as usize;
+            if u16_at(bytes, cell) as usize >= snapshot.styles {
+                return Err(SnapshotError::new("unknown style"));
+            }
+            if offset + symbol_len > bytes.len() {
+                return Err(SnapshotError::new("symbols overrun the end"));
+            }
+            let (_, rest) = bytes.split_at(offset);
+            let (symbol, _) = rest.split_at(symbol_len);
+            if core::str::from_utf8(symbol).is_err() {
+                return Err(SnapshotError::new("symbol isn't UTF-8"));
+            }
+            offset += symbol_len;
+            i += 1;
+        }
+        if offset != bytes.len() {
+            return Err(SnapshotError::new("symbols don't add up to the header"));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Like [`new()`](Self::new), failing the build when used in a constant
+    pub const fn from_static(bytes: &'static [u8]) -> Snapshot<'static> {
+        match Snapshot::new(bytes) {
+            Ok(snapshot) => snapshot,
+            Err(err) => panic!("{}", err.message),
+        }
+    }
+
+    /// The area of the buffer the snapshot was captured in
+    pub const fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Number of cells
+    pub const fn len(&self) -> usize {
+        self.cells
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.cells == 0
+    }
+
+    /// The encoded snapshot
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Writes every cell into `buf`, skipping runs outside its area
+    pub fn replay(&self, buf: &mut Buffer) {
+        let mut cell = self.cells_start();
+        let mut symbol = self.symbols_start();
+
+        for run in 0..self.runs {
+            let run = self.runs_start() + run * RUN_LEN;
+            let (x, y, len) = (u16_at(self.bytes, run), u16_at(self.bytes, run + 2), u16_at(self.bytes, run + 4));
+            let inside = buf.area.contains(Position::new(x, y)) && u32::from(x) + u32::from(len) <= u32::from(buf.area.right());
+            let start = if inside { buf.index_of(x, y) } else { 0 };
+
+            for i in 0..usize::from(len) {
+                let style = usize::from(u16_at(self.bytes, cell));
+                let end = symbol + usize::from(u16_at(self.bytes, cell + 2));
+                if inside {
+                    let text = std::str::from_utf8(&self.bytes[symbol..end]).unwrap_or(" ");
+                    self.apply(style, &mut buf.content[start + i], text);
+                }
+                cell += CELL_LEN;
+                symbol = end;
+            }
+        }
+    }
+
+    /// Replays the snapshot if `buf` has the area it was captured in, and calls `render` otherwise
+    pub fn replay_or(&self, buf: &mut Buffer, render: impl FnOnce(&mut Buffer)) {
+        if buf.area == self.area {
+            self.replay(buf);
+        } else {
+            render(buf);
+        }
+    }
+
+    #[allow(deprecated)]
+    fn apply(&self, style: usize, cell: &mut Cell, symbol: &str) {
+        let style = &self.bytes[self.style_offset(style)..][..STYLE_LEN];
+        cell.set_symbol(symbol);
+        cell.fg = decode_color(&style[0..4]);
+        cell.bg = decode_color(&style[4..8]);
+        cell.modifier = Modifier::from_bits_truncate(u16_at(style, 8));
+        cell.skip = style[10] != 0;
+        cell.diff_option = match style[11] {
+            1 => CellDiffOption::Skip,
+            2 => CellDiffOption::AlwaysUpdate,
+            3 => CellDiffOption::ForcedWidth(NonZeroU16::new(u16_at(style, 12)).unwrap_or(NonZeroU16::MIN)),
+            _ => CellDiffOption::None,
+        };
+    }
+
+    const fn style_offset(&self, style: usize) -> usize {
+        HEADER_LEN + style * STYLE_LEN
+    }
+
+    const fn runs_start(&self) -> usize {
+        self.style_offset(self.styles)
+    }
+
+    const fn cells_start(&self) -> usize {
+        self.runs_start().saturating_add(self.runs.saturating_mul(RUN_LEN))
+    }
+
+    const fn symbols_start(&self) -> usize {
+        self.cells_start().saturating_add(self.cells.saturating_mul(CELL_LEN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Style, Stylize};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::Widget;
+    use crate::worm_buffer::WormBuffer;
+
+    fn render(buf: &mut Buffer) {
+        Line::from(vec![
+            "red".red().bold(),
+            Span::styled("┃é", Style::new().bg(Color::Rgb(1, 2, 3))),
+            Span::styled("⣿", Style::new().fg(Color::Indexed(200))),
+        ])
+            .render(Rect::new(2, 1, 8, 1), buf);
+        buf[(0, 2)].set_symbol("x").set_diff_option(CellDiffOption::ForcedWidth(NonZeroU16::new(2).unwrap()));
+    }
+
+    #[test]
+    fn test_snapshot_replays_the_capture() {
+        let area = Rect::new(0, 0, 10, 3);
+        let bytes = capture(area, render);
+        let snapshot = Snapshot::new(&bytes).unwrap();
+        assert_eq!(snapshot.area(), area);
+        assert_eq!(snapshot.len(), 7);
+
+        let mut expected = Buffer::empty(area);
+        render(&mut expected);
+        let mut actual = Buffer::filled(area, Cell::new("#").set_style(Modifier::ITALIC).clone());
+        snapshot.replay(&mut actual);
+        let mut replayed = Buffer::empty(area);
+        snapshot.replay(&mut replayed);
+        assert_eq!(replayed, expected);
+        assert_eq!(actual[(4, 1)], expected[(4, 1)]);
+
+        // a WormBuffer capture encodes to the same bytes
+        let worm_buffer = WormBuffer::new();
+        worm_buffer.cached_render(area, &mut Buffer::empty(area), render);
+        assert_eq!(worm_buffer.to_snapshot(area), bytes);
+    }
+
+    #[test]
+    fn test_corrupt_snapshots_are_rejected() {
+        let bytes = capture(Rect::new(0, 0, 10, 3), render);
+        assert!(Snapshot::new(&bytes[..bytes.len() - 1]).is_err());
+        assert_eq!(Snapshot::new(&bytes[..10]), Err(SnapshotError::new("shorter than the header")));
+
+        let mut bad = bytes.clone();
+        bad[4] = 2;
+        assert_eq!(Snapshot::new(&bad), Err(SnapshotError::new("unsupported version")));
+
+        // the last symbol, `⣿`, cut into an invalid byte
+        let mut bad = bytes.clone();
+        let last = bad.len() - 1;
+        bad[last] = 0xFF;
+        assert_eq!(Snapshot::new(&bad), Err(SnapshotError::new("symbol isn't UTF-8")));
+    }
+
+    #[test]
+    fn test_baked_snapshots_are_current() {
+        for baked in BAKED {
+            assert!(
+                baked.capture() == baked.snapshot.bytes(),
+                "src/snapshots/{} is stale; run mousefood-snapshot",
+                baked.file,
+            );
+        }
+    }
+}
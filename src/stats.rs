@@ -1,8 +1,4 @@
-use esp_idf_svc::hal::delay;
-use esp_idf_svc::hal::gpio::{Gpio0, Input, PinDriver};
-use esp_idf_svc::hal::task::notification::Notification;
-use esp_idf_svc::sys::{heap_caps_get_free_size, heap_caps_get_total_size, MALLOC_CAP_8BIT};
-use ratatui::prelude::{Backend, Color, Terminal};
+use ratatui::prelude::{Color, Frame};
 use ratatui::widgets::BorderType;
 use ratatui::{
     buffer::Buffer,
@@ -11,94 +7,118 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Gauge, Padding, Paragraph, Widget},
 };
-use std::marker::PhantomData;
 use compact_str::format_compact;
 use ratatui::layout::{Margin, Size};
+use std::time::Duration;
 use crate::catpuccin::CATPPUCCIN;
 use crate::fps::FpsWidget;
+use crate::scene::Scene;
 use crate::header::render_header;
+use crate::platform::memory_info;
+use crate::snapshot::STATS_CHROME;
+use crate::cell_store::RunList;
 use crate::worm_buffer::WormBuffer;
 
+/// How often the memory numbers are read again
+const MEMORY_REFRESH: Duration = Duration::from_secs(1);
+
+/// System statistics; everything but the memory numbers is replayed from
+/// [`STATS_CHROME`], baked at build time
 #[derive(Debug)]
-pub struct Stats<B: Backend> {
-    fps_widget: FpsWidget,
-    worm_buffer: WormBuffer,
-    _marker: PhantomData<B>,
+pub struct Stats {
+    /// The memory lines, captured again when the numbers change
+    memory_cache: WormBuffer<RunList>,
+    /// Free and total memory, as of the last refresh
+    memory: (usize, usize),
+    /// Time since the memory numbers were last refreshed
+    since_refresh: Duration,
 }
 
-impl<B: Backend> Stats<B> {
+impl Stats {
     pub fn new() -> Self {
         Self {
-            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
-            worm_buffer: WormBuffer::new(),
-            _marker: PhantomData,
-        }
-    }
-
-    pub fn run(
-        mut self,
-        terminal: &mut Terminal<B>,
-        notification: &mut Notification,
-        button: &mut PinDriver<Gpio0, Input>,
-    ) -> std::io::Result<()> {
-        button.enable_interrupt().unwrap();
-        loop {
-            if notification.wait(delay::NON_BLOCK).is_some() {
-                return Ok(());
-            }
-            self.fps_widget.fps.tick();
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))
-                .unwrap();
+            memory_cache: WormBuffer::default(),
+            memory: memory_info(),
+            since_refresh: Duration::ZERO,
         }
     }
 }
 
-impl<B: Backend> Default for Stats<B> {
+impl Default for Stats {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<B: Backend> Widget for &Stats<B> {
-    #[allow(clippy::similar_names)]
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        self.worm_buffer.cached_render(area, buf, |buf| {
-            let layout = Layout::vertical([
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(3),
-            ]).split(area);
-
-            self.render_header(layout[0], buf);
-            self.render_content(layout[1], buf);
+impl Scene for Stats {
+    fn update(&mut self, elapsed: Duration) {
+        self.since_refresh += elapsed;
+        if self.since_refresh >= MEMORY_REFRESH {
+            self.since_refresh = Duration::ZERO;
+            self.memory = memory_info();
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let memory_area = Self::memory_area(Self::layout(area)[1]);
+
+        STATS_CHROME.replay_or(buf, |buf| Self::render_chrome(area, buf));
+        self.memory_cache.cached_render_keyed(&self.memory, memory_area, buf, |buf| {
+            Self::render_memory(self.memory, memory_area, buf);
         });
 
-        self.render_footer(Rect::new(6, 23, 53 - 6, 1), buf);
+        self.render_footer(Rect::new(6, 23, 53 - 6, 1), buf, fps);
     }
 }
 
-impl<B: Backend> Stats<B> {
-    fn get_memory_info(&self) -> (usize, usize) {
-        unsafe {
-            let free = heap_caps_get_free_size(MALLOC_CAP_8BIT) as usize;
-            let total = heap_caps_get_total_size(MALLOC_CAP_8BIT) as usize;
-            (free, total)
-        }
+impl Stats {
+    /// Renders everything but the memory numbers, as baked into [`STATS_CHROME`]
+    pub fn render_chrome(area: Rect, buf: &mut Buffer) {
+        let layout = Self::layout(area);
+        render_header(layout[0], buf, "Mousefood Benchmark", CATPPUCCIN.mauve);
+        Self::render_content(layout[1], buf);
     }
 
-    fn render_header(&self, area: Rect, buf: &mut Buffer) {
-        render_header(area, buf, "Mousefood Benchmark", CATPPUCCIN.mauve);
+    fn layout(area: Rect) -> [Rect; 3] {
+        Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(3),
+        ]).areas(area)
     }
 
-    fn render_content(&self, area: Rect, buf: &mut Buffer) {
+    /// The "Memory" and "Free" lines of the content
+    fn memory_area(area: Rect) -> Rect {
         let inner = area.inner(Margin::new(6, 1));
-        
-        let terminal_size = format!("Terminal: {}x{}", area.width, area.height);
-        let (free_memory, total_memory) = self.get_memory_info();
+        Rect::new(inner.x, inner.y + 3, inner.width, 2).intersection(inner)
+    }
+
+    #[allow(clippy::similar_names)]
+    fn render_memory((free_memory, total_memory): (usize, usize), area: Rect, buf: &mut Buffer) {
         let used_memory = total_memory - free_memory;
-        let memory_usage = format!("Memory: {}KB used / {}KB total", used_memory / 1024, total_memory / 1024);
-        let free_memory_str = format!("Free: {}KB", free_memory / 1024);
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
+                Span::styled("Memory: ", Style::default().fg(CATPPUCCIN.blue).bold()),
+                Span::styled(format_compact!("{}KB used / {}KB total", used_memory / 1024, total_memory / 1024), Style::default().fg(CATPPUCCIN.text)),
+            ]),
+            Line::from(vec![
+                Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
+                Span::styled("Free: ", Style::default().fg(CATPPUCCIN.blue).bold()),
+                Span::styled(format_compact!("{}KB", free_memory / 1024), Style::default().fg(CATPPUCCIN.text)),
+            ]),
+        ];
 
+        Paragraph::new(lines)
+            .style(Style::default().fg(CATPPUCCIN.text))
+            .render(area, buf);
+    }
+
+    /// The content, leaving the memory lines blank
+    fn render_content(area: Rect, buf: &mut Buffer) {
+        let inner = area.inner(Margin::new(6, 1));
         let screen_area = buf.area;
 
         let content = vec![
@@ -111,16 +131,8 @@ impl<B: Backend> Stats<B> {
                 Span::styled("Terminal: ", Style::default().fg(CATPPUCCIN.blue).bold()),
                 Span::styled(format_compact!("{}x{}", screen_area.width, screen_area.height), Style::default().fg(CATPPUCCIN.text)),
             ]),
-            Line::from(vec![
-                Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
-                Span::styled("Memory: ", Style::default().fg(CATPPUCCIN.blue).bold()),
-                Span::styled(format_compact!("{}KB used / {}KB total", used_memory / 1024, total_memory / 1024), Style::default().fg(CATPPUCCIN.text)),
-            ]),
-            Line::from(vec![
-                Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
-                Span::styled("Free: ", Style::default().fg(CATPPUCCIN.blue).bold()),
-                Span::styled(format_compact!("{}KB", free_memory / 1024), Style::default().fg(CATPPUCCIN.text)),
-            ]),
+            Line::from(""),
+            Line::from(""),
             Line::from(vec![
                 Span::styled("• ", Style::default().fg(CATPPUCCIN.green)),
                 Span::styled("Status: ", Style::default().fg(CATPPUCCIN.blue).bold()),
@@ -147,9 +159,9 @@ impl<B: Backend> Stats<B> {
         paragraph.render(inner, buf);
     }
 
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
         let fps_area = area.inner(Margin::new(8, 0));
-        self.fps_widget.render(fps_area, buf);
+        fps.render(fps_area, buf);
     }
 }
 
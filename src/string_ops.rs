@@ -1,7 +1,4 @@
-use esp_idf_svc::hal::delay;
-use esp_idf_svc::hal::gpio::{Gpio0, Input, PinDriver};
-use esp_idf_svc::hal::task::notification::Notification;
-use ratatui::prelude::{Backend, Terminal};
+use ratatui::Frame;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -9,9 +6,8 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Widget, Table, Row, Cell},
 };
-use std::marker::PhantomData;
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use compact_str::{format_compact, CompactString, ToCompactString};
 use mousefood::embedded_graphics::mono_font::MonoFont;
 use ratatui::layout::{Alignment, Margin};
@@ -19,254 +15,149 @@ use ratatui::text::Text;
 use crate::catpuccin::CATPPUCCIN;
 use crate::embedded_str::EmbeddedStr;
 use crate::fps::FpsWidget;
+use crate::scene::Scene;
 use crate::header::render_header;
+use crate::microbench::{BenchState, Harness, Suite};
+use crate::worm_buffer::WormBuffer;
 
-const ITERATIONS: u32 = 1_000_000;
-
-#[derive(Debug, Clone, Default)]
-struct StringBenchmarkResults {
-    from_ascii_str: Option<u32>,
-    from_ascii_ch: Option<u32>,
-    from_block_str: Option<u32>,
-    from_block_ch: Option<u32>,
-    as_str: Option<u32>,
-}
-
-
-#[derive(Debug, Clone, Default)]
-struct BenchmarkResults {
-    compact_str: StringBenchmarkResults,
-    embedded_str: StringBenchmarkResults,
-}
-
-impl BenchmarkResults {
-    fn new() -> Self {
-        Self::default()
-    }
-}
+/// Iterations per sample
+const ITERATIONS: u32 = 100_000;
 
+const GROUPS: [(&str, Color); 2] = [("compact", CATPPUCCIN.green), ("embedded", CATPPUCCIN.teal)];
 
 #[derive(Debug)]
-pub struct StringOpsApp<'a, B: Backend> {
-    atlas_font: &'a MonoFont<'a>,
-    results: BenchmarkResults,
-    current_benchmark: usize,
-    fps_widget: FpsWidget,
-    _marker: PhantomData<B>,
+pub struct StringOpsApp {
+    atlas_font: MonoFont<'static>,
+    suite: Suite,
+    worm_buffer: WormBuffer,
 }
 
-impl<'a, B: Backend> StringOpsApp<'a, B> {
-    pub fn new(atlas_font: &'a MonoFont<'a>) -> Self {
-        Self {
-            atlas_font,
-            results: BenchmarkResults::new(),
-            current_benchmark: 0,
-            fps_widget: FpsWidget::new().with_label(true).with_style(CATPPUCCIN.green),
-            _marker: PhantomData,
-        }
-    }
-
-    fn run_next_benchmark(&mut self) {
-        if self.current_benchmark >= 10 {
-            return;
-        }
-
-        let duration_ms = match self.current_benchmark {
+impl StringOpsApp {
+    pub fn new(atlas_font: MonoFont<'static>) -> Self {
+        let suite = Suite::new(Harness::default())
             // CompactString benchmarks
-            0 => self.benchmark_from_ascii_str(|s| CompactString::from(s)),
-            1 => self.benchmark_from_ascii_char(|c| c.to_compact_string()),
-            2 => self.benchmark_from_block_str(|s| CompactString::from(s)),
-            3 => self.benchmark_from_block_char(|c| c.to_compact_string()),
-            4 => self.benchmark_as_str_compact(),
+            .bench("AsciiStr", "compact", ITERATIONS, from_str(ascii(), |s| CompactString::from(s)))
+            .bench("AsciiCh", "compact", ITERATIONS, from_char(ascii(), |c| c.to_compact_string()))
+            .bench("BlockStr", "compact", ITERATIONS, from_str(block(), |s| CompactString::from(s)))
+            .bench("BlockCh", "compact", ITERATIONS, from_char(block(), |c| c.to_compact_string()))
+            .bench("AsStr", "compact", ITERATIONS, as_str(ascii().map(|c| c.to_compact_string()), CompactString::as_str))
             // EmbeddedStr benchmarks
-            5 => self.benchmark_from_ascii_str(|s|EmbeddedStr::from(s)),
-            6 => self.benchmark_from_ascii_char(EmbeddedStr::from),
-            7 => self.benchmark_from_block_str(|s| EmbeddedStr::from(s)),
-            8 => self.benchmark_from_block_char(EmbeddedStr::from),
-            9 => self.benchmark_as_str_embedded(),
-            _ => 0,
-        };
-
-        match self.current_benchmark {
-            // CompactString results
-            0 => self.results.compact_str.from_ascii_str = Some(duration_ms),
-            1 => self.results.compact_str.from_ascii_ch = Some(duration_ms),
-            2 => self.results.compact_str.from_block_str = Some(duration_ms),
-            3 => self.results.compact_str.from_block_ch = Some(duration_ms),
-            4 => self.results.compact_str.as_str = Some(duration_ms),
-            // EmbeddedStr results
-            5 => self.results.embedded_str.from_ascii_str = Some(duration_ms),
-            6 => self.results.embedded_str.from_ascii_ch = Some(duration_ms),
-            7 => self.results.embedded_str.from_block_str = Some(duration_ms),
-            8 => self.results.embedded_str.from_block_ch = Some(duration_ms),
-            9 => self.results.embedded_str.as_str = Some(duration_ms),
-            _ => {}
-        };
+            .bench("AsciiStr", "embedded", ITERATIONS, from_str(ascii(), |s| EmbeddedStr::from(s)))
+            .bench("AsciiCh", "embedded", ITERATIONS, from_char(ascii(), EmbeddedStr::from))
+            .bench("BlockStr", "embedded", ITERATIONS, from_str(block(), |s| EmbeddedStr::from(s)))
+            .bench("BlockCh", "embedded", ITERATIONS, from_char(block(), EmbeddedStr::from))
+            .bench("AsStr", "embedded", ITERATIONS, as_str(ascii().map(EmbeddedStr::from), EmbeddedStr::as_str));
 
-        self.current_benchmark += 1;
-    }
-
-    fn benchmark_from_ascii_char<T>(&self, f: impl Fn(char) -> T) -> u32 {
-        let chars: Vec<char> = (0x20..0x7F)
-            .map(|v| char::from_u32(v).unwrap())
-            .collect();
-
-        let mut input = chars.iter().cycle();
-        
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let ch = input.next().copied().unwrap();
-            core::hint::black_box(f(ch));
-        }
-        start.elapsed().as_millis() as u32
-    }
-
-    fn benchmark_from_ascii_str<T>(&self, f: impl Fn(&str) -> T) -> u32 {
-        let chars: Vec<String> = (0x20..0x7F)
-            .map(|v| char::from_u32(v).unwrap())
-            .map(|c| c.to_string())
-            .collect();
-
-        let mut input = chars.iter().cycle();
-
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let s = input.next().unwrap();
-            core::hint::black_box(f(s));
-        }
-        start.elapsed().as_millis() as u32
+        Self { atlas_font, suite, worm_buffer: WormBuffer::new() }
     }
+}
 
-    fn benchmark_from_block_str<T>(&self, f: impl Fn(&str) -> T) -> u32 {
-        let chars: Vec<String> = (0x2580..0x259F)
-            .filter_map(char::from_u32)
-            .map(|c| c.to_string())
-            .collect();
-
-        let mut input = chars.iter().cycle();
+fn ascii() -> impl Iterator<Item = char> {
+    (0x20..0x7F).filter_map(char::from_u32)
+}
 
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let s = input.next().unwrap();
-            core::hint::black_box(f(s));
-        }
-        start.elapsed().as_millis() as u32
-    }
+fn block() -> impl Iterator<Item = char> {
+    (0x2580..0x259F).filter_map(char::from_u32)
+}
 
-    fn benchmark_from_block_char<T>(&self, f: impl Fn(char) -> T) -> u32 {
-        let chars: Vec<char> = (0x2580..0x259F)
-            .filter_map(char::from_u32)
-            .collect();
+/// Converts each char in turn, cycling through them
+fn from_char<T>(chars: impl Iterator<Item = char>, f: impl Fn(char) -> T) -> impl FnMut(u32) {
+    let chars: Vec<char> = chars.collect();
+    let mut input = chars.into_iter().cycle();
 
-        let mut input = chars.iter().cycle();
-        
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let ch = input.next().copied().unwrap();
+    move |n| {
+        for _ in 0..n {
+            let ch = input.next().unwrap();
             core::hint::black_box(f(ch));
         }
-        start.elapsed().as_millis() as u32
     }
+}
 
-    fn benchmark_as_str_compact(&self) -> u32 {
-        let strings: Vec<CompactString> = (0x20..0x7F)
-            .map(|v| char::from_u32(v).unwrap())
-            .map(|c| c.to_compact_string())
-            .collect();
+/// Converts each char, as a one-char string, in turn, cycling through them
+fn from_str<T>(chars: impl Iterator<Item = char>, f: impl Fn(&str) -> T) -> impl FnMut(u32) {
+    let strings: Vec<String> = chars.map(|c| c.to_string()).collect();
+    let mut idx = 0;
 
-        let mut input = strings.iter().cycle();
-        
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let s = input.next().unwrap();
-            core::hint::black_box(s.as_str());
+    move |n| {
+        for _ in 0..n {
+            core::hint::black_box(f(&strings[idx]));
+            idx = (idx + 1) % strings.len();
         }
-        start.elapsed().as_millis() as u32
     }
+}
 
-    fn benchmark_as_str_embedded(&self) -> u32 {
-        let strings: Vec<EmbeddedStr> = (0x20..0x7F)
-            .map(|v| char::from_u32(v).unwrap())
-            .map(EmbeddedStr::from)
-            .collect();
+/// Borrows each string in turn, cycling through them
+fn as_str<T>(strings: impl Iterator<Item = T>, f: fn(&T) -> &str) -> impl FnMut(u32) {
+    let strings: Vec<T> = strings.collect();
+    let mut idx = 0;
 
-        let mut input = strings.iter().cycle();
-        
-        let start = Instant::now();
-        for _ in 0..ITERATIONS {
-            let s = input.next().unwrap();
-            core::hint::black_box(s.as_str());
+    move |n| {
+        for _ in 0..n {
+            core::hint::black_box(f(&strings[idx]));
+            idx = (idx + 1) % strings.len();
         }
-        start.elapsed().as_millis() as u32
     }
+}
 
 
-    pub fn run(
-        mut self,
-        terminal: &mut Terminal<B>,
-        notification: &mut Notification,
-        button: &mut PinDriver<Gpio0, Input>,
-    ) -> std::io::Result<()> {
-        button.enable_interrupt().unwrap();
-        
-        loop {
-            if notification.wait(delay::NON_BLOCK).is_some() {
-                return Ok(());
-            }
-            
-            // Run next benchmark if available
-            if self.current_benchmark < 10 {
-                self.run_next_benchmark();
-            }
-            
-            self.fps_widget.fps.tick();
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))
-                .unwrap();
-            
-            thread::sleep(Duration::from_millis(16)); // ~60 FPS
-        }
-    }
-}
+impl Scene for StringOpsApp {
+    fn update(&mut self, _elapsed: Duration) {
+        self.suite.run_next();
 
+        thread::sleep(Duration::from_millis(16)); // ~60 FPS
+    }
 
-impl<'a, B: Backend> Widget for &StringOpsApp<'a, B> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
         let layout = Layout::vertical([
             Constraint::Length(3),
             Constraint::Percentage(100),
             Constraint::Length(3),
         ]).split(area);
 
-        self.render_header(layout[0], buf);
-        self.render_results(layout[1], buf);
-        self.render_footer(layout[2], buf);
+        // the screen only changes when a benchmark finishes
+        self.worm_buffer.cached_render_keyed(&self.suite.completed(), area, buf, |buf| {
+            self.render_header(layout[0], buf);
+            self.render_results(layout[1], buf);
+        });
+        self.render_footer(layout[2], buf, fps);
+    }
+    fn is_busy(&self) -> bool {
+        !self.suite.is_done()
+    }
+
+    fn suite(&self) -> Option<&Suite> {
+        Some(&self.suite)
+    }
+
+    fn summary(&self) -> Option<CompactString> {
+        Some(if self.suite.is_done() {
+            format_compact!("{} ms", self.suite.total().as_millis())
+        } else {
+            format_compact!("{}/{} done", self.suite.completed(), self.suite.len())
+        })
     }
 }
 
-impl<'a, B: Backend> StringOpsApp<'a, B> {
+impl StringOpsApp {
     fn render_header(&self, area: Rect, buf: &mut Buffer) {
-        let progress = self.current_benchmark.min(10);
-        let title = format_compact!("String Operations Benchmark [{}/10]", progress);
+        let title = format_compact!("String Operations Benchmark [{}/{}]", self.suite.completed(), self.suite.len());
         render_header(area, buf, &title, CATPPUCCIN.blue);
     }
 
     fn render_results(&self, area: Rect, buf: &mut Buffer) {
-        let header = Row::new(vec![
-            Cell::from("Operation").style(Style::default().fg(CATPPUCCIN.text)),
-            Cell::from("Compact ").style(Style::default().fg(CATPPUCCIN.green)),
-            Cell::from("Embedded").style(Style::default().fg(CATPPUCCIN.teal)),
-            Cell::from(" Ratio ").style(Style::default().fg(CATPPUCCIN.peach)),
-        ]);
+        let header = Row::new(
+            [Cell::from("ns/iter  ").style(Style::default().fg(CATPPUCCIN.text))].into_iter()
+                .chain(GROUPS.map(|(group, color)| Cell::from(group).style(Style::default().fg(color))))
+                .chain([Cell::from(" Ratio ").style(Style::default().fg(CATPPUCCIN.peach))])
+        );
 
-        let rows = vec![
-            self.create_string_row("AsciiStr ", self.results.compact_str.from_ascii_str, self.results.embedded_str.from_ascii_str, 0, 5),
-            self.create_string_row("AsciiCh  ", self.results.compact_str.from_ascii_ch, self.results.embedded_str.from_ascii_ch, 1, 6),
-            self.create_string_row("BlockStr ", self.results.compact_str.from_block_str, self.results.embedded_str.from_block_str, 2, 7),
-            self.create_string_row("BlockCh  ", self.results.compact_str.from_block_ch, self.results.embedded_str.from_block_ch, 3, 8),
-            self.create_string_row("AsStr    ", self.results.compact_str.as_str, self.results.embedded_str.as_str, 4, 9),
-        ];
+        let rows = self.suite.names_in(&GROUPS.map(|(group, _)| group))
+            .into_iter()
+            .map(|name| self.create_string_row(name));
 
-        let table = Table::new(rows, [Constraint::Length(9), Constraint::Length(8), Constraint::Length(8), Constraint::Length(7)])
+        let table = Table::new(rows, [Constraint::Length(9), Constraint::Length(10), Constraint::Length(10), Constraint::Length(7)])
             .header(header)
             .block(Block::new());
 
@@ -279,53 +170,47 @@ impl<'a, B: Backend> StringOpsApp<'a, B> {
         table.render(table_area, buf);
     }
 
-    fn create_string_row<'b>(&self, operation_name: &'b str, compact_result: Option<u32>, embedded_result: Option<u32>, compact_idx: usize, embedded_idx: usize) -> Row<'b> {
-        let compact_cell = self.format_benchmark_cell(compact_idx, compact_result, CATPPUCCIN.green);
-        let embedded_cell = self.format_benchmark_cell(embedded_idx, embedded_result, CATPPUCCIN.teal);
-        
+    fn create_string_row(&self, operation_name: &'static str) -> Row<'static> {
+        let [(compact_group, compact_color), (embedded_group, embedded_color)] = GROUPS;
+        let compact_state = self.suite.state(operation_name, compact_group);
+        let embedded_state = self.suite.state(operation_name, embedded_group);
+
         // Calculate speed ratio (compact is baseline 1.0x)
-        let ratio_cell = if let (Some(compact_ms), Some(embedded_ms)) = (compact_result, embedded_result) {
-            let ratio = compact_ms as f32 / embedded_ms as f32;
+        let ratio_cell = if let (Some(BenchState::Done(compact_ns)), Some(BenchState::Done(embedded_ns))) = (compact_state, embedded_state) {
+            let ratio = compact_ns.ns_per_iter() / embedded_ns.ns_per_iter();
             let ratio_text = Text::from(format!("{:.1}x", ratio)).alignment(Alignment::Right);
             let color = if ratio > 1.0 { CATPPUCCIN.teal } else { CATPPUCCIN.green };
             Cell::from(ratio_text).style(Style::default().fg(color))
         } else {
-            let color = if compact_idx < self.current_benchmark && embedded_idx < self.current_benchmark {
-                CATPPUCCIN.surface2
-            } else if compact_idx == self.current_benchmark || embedded_idx == self.current_benchmark {
+            let color = if compact_state == Some(BenchState::Running) || embedded_state == Some(BenchState::Running) {
                 CATPPUCCIN.yellow
             } else {
                 CATPPUCCIN.surface2
             };
             Cell::from("---").style(Style::default().fg(color))
         };
-        
+
         Row::new(vec![
-            Cell::from(operation_name).style(Style::default().fg(CATPPUCCIN.text)),
-            compact_cell,
-            embedded_cell,
+            Cell::from(format!("{operation_name:<9}")).style(Style::default().fg(CATPPUCCIN.text)),
+            self.format_benchmark_cell(compact_state, compact_color),
+            self.format_benchmark_cell(embedded_state, embedded_color),
             ratio_cell,
         ])
     }
 
-    fn format_benchmark_cell(&self, bench_idx: usize, result: Option<u32>, completed_color: Color) -> Cell<'static> {
-        let (text, color) = if bench_idx < self.current_benchmark {
-            if let Some(duration) = result {
-                (format!("{duration}"), completed_color)
-            } else {
-                ("Error".to_string(), CATPPUCCIN.red)
-            }
-        } else if bench_idx == self.current_benchmark {
-            ("Running".to_string(), CATPPUCCIN.yellow)
-        } else {
-            ("Pending".to_string(), CATPPUCCIN.surface2)
+    fn format_benchmark_cell(&self, state: Option<BenchState>, completed_color: Color) -> Cell<'static> {
+        let (text, color) = match state {
+            Some(BenchState::Done(measurement)) => (measurement.to_string(), completed_color),
+            Some(BenchState::Running) => ("Running".to_string(), CATPPUCCIN.yellow),
+            Some(BenchState::Pending) => ("Pending".to_string(), CATPPUCCIN.surface2),
+            None => ("Error".to_string(), CATPPUCCIN.red),
         };
-        
+
         Cell::from(Text::from(text).alignment(Alignment::Right)).style(Style::default().fg(color))
     }
 
-    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
         let fps_area = area.inner(Margin::new(8, 0));
-        self.fps_widget.render(fps_area, buf);
+        fps.render(fps_area, buf);
     }
 }
\ No newline at end of file
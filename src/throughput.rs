@@ -0,0 +1,283 @@
+//! Pixel throughput of the display and an estimate of its SPI bus time.
+//!
+//! [`CountingTarget`] wraps the `DrawTarget` handed to `EmbeddedBackend`,
+//! counting the pixels and calls that reach the display and the bytes they
+//! take on the wire. Like [`instrument`](crate::instrument), the counters are
+//! closed per frame by the [`SceneRunner`](crate::scene::SceneRunner).
+
+use std::cell::Cell;
+use std::ops::AddAssign;
+use std::time::Duration;
+use embedded_graphics::prelude::{Dimensions, DrawTarget, Pixel, Point};
+use embedded_graphics::primitives::Rectangle;
+
+/// Bytes sent to open an address window on the ST7789: CASET and RASET with
+/// four parameter bytes each, then RAMWR
+const WINDOW_COMMAND_BYTES: u64 = 11;
+
+/// Command and parameter transfers per address window
+const WINDOW_TRANSACTIONS: u64 = 6;
+
+/// How pixels travel to the panel, as configured in the firmware
+///
+/// The estimate is a lower bound: pure wire time at `baud_hz`, plus
+/// `transaction_overhead` for every SPI transaction, which defaults to none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpiModel {
+    pub baud_hz: u32,
+    /// Size of the interface buffer; larger pixel writes are split into chunks of it
+    pub buffer_bytes: usize,
+    pub bytes_per_pixel: usize,
+    pub transaction_overhead: Duration,
+}
+
+impl Default for SpiModel {
+    /// 80 MHz, an 8192-byte buffer and RGB565, matching `main`
+    fn default() -> Self {
+        Self {
+            baud_hz: 80_000_000,
+            buffer_bytes: 8192,
+            bytes_per_pixel: 2,
+            transaction_overhead: Duration::ZERO,
+        }
+    }
+}
+
+impl SpiModel {
+    /// Estimated bus time of `bytes` sent in `transactions`
+    pub fn bus_time(&self, bytes: u64, transactions: u64) -> Duration {
+        let wire = Duration::from_nanos(bytes * 8 * 1_000_000_000 / u64::from(self.baud_hz.max(1)));
+        wire + self.transaction_overhead * transactions as u32
+    }
+}
+
+/// Display writes of one frame, or summed over many
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PixelStats {
+    /// Pixels written, including those of fills
+    pub pixels: u64,
+    pub draw_iter_calls: u64,
+    pub fill_solid_calls: u64,
+    pub fill_contiguous_calls: u64,
+    /// Address windows opened; `draw_iter` opens one per pixel
+    pub windows: u64,
+    /// Area of the bounding box of everything written in a frame
+    pub bbox_area: u64,
+    /// Pixel data and window commands
+    pub spi_bytes: u64,
+    pub spi_transactions: u64,
+    /// Estimated from the [`SpiModel`]
+    pub spi_time: Duration,
+}
+
+impl AddAssign for PixelStats {
+    fn add_assign(&mut self, other: Self) {
+        self.pixels += other.pixels;
+        self.draw_iter_calls += other.draw_iter_calls;
+        self.fill_solid_calls += other.fill_solid_calls;
+        self.fill_contiguous_calls += other.fill_contiguous_calls;
+        self.windows += other.windows;
+        self.bbox_area += other.bbox_area;
+        self.spi_bytes += other.spi_bytes;
+        self.spi_transactions += other.spi_transactions;
+        self.spi_time += other.spi_time;
+    }
+}
+
+const NONE: PixelStats = PixelStats {
+    pixels: 0,
+    draw_iter_calls: 0,
+    fill_solid_calls: 0,
+    fill_contiguous_calls: 0,
+    windows: 0,
+    bbox_area: 0,
+    spi_bytes: 0,
+    spi_transactions: 0,
+    spi_time: Duration::ZERO,
+};
+
+thread_local! {
+    /// Counted since the last frame was closed
+    static CURRENT: Cell<PixelStats> = const { Cell::new(NONE) };
+    /// Corners of everything written since the last frame was closed
+    static BBOX: Cell<Option<(Point, Point)>> = const { Cell::new(None) };
+    static LAST_FRAME: Cell<PixelStats> = const { Cell::new(NONE) };
+}
+
+/// Counters of the most recently finished frame
+pub fn last_frame() -> PixelStats {
+    LAST_FRAME.with(Cell::get)
+}
+
+/// Closes the current frame, returning its counters
+pub(crate) fn finish_frame() -> PixelStats {
+    let mut stats = CURRENT.with(Cell::take);
+    if let Some((top_left, bottom_right)) = BBOX.with(Cell::take) {
+        let size = bottom_right - top_left + Point::new(1, 1);
+        stats.bbox_area = size.x as u64 * size.y as u64;
+    }
+
+    LAST_FRAME.with(|last| last.set(stats));
+    stats
+}
+
+/// `DrawTarget` decorator counting what is written to the display
+pub struct CountingTarget<D> {
+    inner: D,
+    spi: SpiModel,
+}
+
+impl<D: DrawTarget> CountingTarget<D> {
+    pub fn new(inner: D, spi: SpiModel) -> Self {
+        Self { inner, spi }
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Counts `windows` address windows of `pixels_per_window` pixels each, written within `area`
+    fn record(&self, f: impl FnOnce(&mut PixelStats), windows: u64, pixels_per_window: u64, area: Rectangle) {
+        let bytes_per_window = pixels_per_window * self.spi.bytes_per_pixel as u64;
+        let chunks = bytes_per_window.div_ceil(self.spi.buffer_bytes.max(1) as u64);
+        let bytes = windows * (WINDOW_COMMAND_BYTES + bytes_per_window);
+        let transactions = windows * (WINDOW_TRANSACTIONS + chunks);
+
+        CURRENT.with(|current| {
+            let mut stats = current.get();
+            f(&mut stats);
+            stats.pixels += windows * pixels_per_window;
+            stats.windows += windows;
+            stats.spi_bytes += bytes;
+            stats.spi_transactions += transactions;
+            stats.spi_time += self.spi.bus_time(bytes, transactions);
+            current.set(stats);
+        });
+
+        if let Some(bottom_right) = area.bottom_right() {
+            BBOX.with(|bbox| {
+                let corners = match bbox.get() {
+                    Some((tl, br)) => (tl.component_min(area.top_left), br.component_max(bottom_right)),
+                    None => (area.top_left, bottom_right),
+                };
+                bbox.set(Some(corners));
+            });
+        }
+    }
+}
+
+impl<D: DrawTarget> Dimensions for CountingTarget<D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.inner.bounding_box()
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for CountingTarget<D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        let mut count = 0;
+        let mut top_left = Point::new(i32::MAX, i32::MAX);
+        let mut bottom_right = Point::new(i32::MIN, i32::MIN);
+
+        // the display drops pixels outside of its bounds without sending them
+        let pixels = pixels.into_iter().inspect(|Pixel(point, _)| {
+            if bounds.contains(*point) {
+                count += 1;
+                top_left = top_left.component_min(*point);
+                bottom_right = bottom_right.component_max(*point);
+            }
+        });
+        let result = self.inner.draw_iter(pixels);
+
+        let area = if count > 0 {
+            Rectangle::with_corners(top_left, bottom_right)
+        } else {
+            Rectangle::zero()
+        };
+        self.record(|stats| stats.draw_iter_calls += 1, count, 1, area);
+        result
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = area.intersection(&self.bounding_box());
+        let pixels = u64::from(clipped.size.width) * u64::from(clipped.size.height);
+        self.record(|stats| stats.fill_contiguous_calls += 1, u64::from(pixels > 0), pixels, clipped);
+        self.inner.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let clipped = area.intersection(&self.bounding_box());
+        let pixels = u64::from(clipped.size.width) * u64::from(clipped.size.height);
+        self.record(|stats| stats.fill_solid_calls += 1, u64::from(pixels > 0), pixels, clipped);
+        self.inner.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let bounds = self.bounding_box();
+        self.fill_solid(&bounds, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+    use embedded_graphics::prelude::Size;
+    use crate::framebuffer::Framebuffer;
+
+    fn target() -> CountingTarget<Framebuffer> {
+        CountingTarget::new(Framebuffer::new(Size::new(100, 50)), SpiModel::default())
+    }
+
+    #[test]
+    fn test_counts_pixels_calls_and_bbox() {
+        let mut target = target();
+        finish_frame();
+
+        target.draw_iter([
+            Pixel(Point::new(1, 2), Rgb565::RED),
+            Pixel(Point::new(3, 4), Rgb565::RED),
+            Pixel(Point::new(500, 4), Rgb565::RED),
+        ]).unwrap();
+        target.fill_solid(&Rectangle::new(Point::new(10, 10), Size::new(5, 2)), Rgb565::BLUE).unwrap();
+        target.fill_contiguous(&Rectangle::new(Point::new(98, 0), Size::new(4, 1)), [Rgb565::GREEN; 4]).unwrap();
+
+        let stats = finish_frame();
+        assert_eq!(stats.pixels, 2 + 10 + 2);
+        assert_eq!((stats.draw_iter_calls, stats.fill_solid_calls, stats.fill_contiguous_calls), (1, 1, 1));
+        assert_eq!(stats.windows, 4);
+        // from (1, 0) to (99, 11)
+        assert_eq!(stats.bbox_area, 99 * 12);
+        assert_eq!(stats.spi_bytes, 14 * 2 + 4 * WINDOW_COMMAND_BYTES);
+        assert_eq!(last_frame(), stats);
+        assert_eq!(target.inner().pixel(Point::new(99, 0)), Some(Rgb565::GREEN));
+    }
+
+    #[test]
+    fn test_full_screen_flush_is_split_into_buffer_chunks() {
+        let mut target = CountingTarget::new(Framebuffer::new(Size::new(320, 240)), SpiModel::default());
+        finish_frame();
+
+        target.clear(Rgb565::BLACK).unwrap();
+        let stats = finish_frame();
+
+        let bytes = 320 * 240 * 2;
+        assert_eq!(stats.spi_bytes, bytes + WINDOW_COMMAND_BYTES);
+        assert_eq!(stats.spi_transactions, WINDOW_TRANSACTIONS + bytes.div_ceil(8192));
+        // 1.23 Mbit at 80 MHz
+        assert_eq!(stats.spi_time.as_micros(), 15361);
+    }
+}
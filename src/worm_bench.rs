@@ -0,0 +1,294 @@
+use ratatui::Frame;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Cell, Table, Widget, Wrap},
+};
+use std::thread;
+use std::time::Duration;
+use compact_str::{format_compact, CompactString};
+use ratatui::layout::{Alignment, Margin};
+use ratatui::text::Text;
+use crate::catpuccin::CATPPUCCIN;
+use crate::cell_store::{CellList, CellStore, RunList};
+use crate::corpus::{self, Length, Words};
+use crate::fps::FpsWidget;
+use crate::scene::Scene;
+use crate::header::render_header;
+use crate::microbench::{BenchState, Harness, Suite};
+use crate::worm_buffer::{MemoryUsage, WormBuffer};
+
+/// Iterations per sample
+const ITERATIONS: u32 = 20;
+
+const GROUPS: [(&str, Color); 2] = [("cells", CATPPUCCIN.green), ("runs", CATPPUCCIN.teal)];
+
+/// Size of the screens captured, the panel's terminal size
+const SCREEN: Rect = Rect::new(0, 0, 53, 24);
+
+/// Capture and replay of a `WormBuffer` with each cell store
+///
+/// Two screens are measured: one filled with colored text, and a mostly blank
+/// one like the statistics screen. Under the timings, the table lists the heap
+/// each store takes for a capture of either screen.
+#[derive(Debug)]
+pub struct WormBenchApp {
+    suite: Suite,
+    /// Heap taken by a capture of each screen, with [`CellList`] and with [`RunList`]
+    memory: [(&'static str, MemoryUsage, MemoryUsage); 2],
+    worm_buffer: WormBuffer,
+}
+
+impl WormBenchApp {
+    pub fn new() -> Self {
+        // each screen with the names of its capture and replay benchmarks
+        let screens = [
+            ("Text", "CapText", "RepText", text_screen()),
+            ("Sparse", "CapSparse", "RepSparse", sparse_screen()),
+        ];
+
+        let mut suite = Suite::new(Harness::default());
+        for (_, capture_name, replay_name, screen) in &screens {
+            suite = suite
+                .bench(capture_name, "cells", ITERATIONS, capture::<CellList>(screen.clone()))
+                .bench(capture_name, "runs", ITERATIONS, capture::<RunList>(screen.clone()))
+                .bench(replay_name, "cells", ITERATIONS, replay::<CellList>(screen.clone()))
+                .bench(replay_name, "runs", ITERATIONS, replay::<RunList>(screen.clone()));
+        }
+
+        Self {
+            suite,
+            memory: screens.map(|(name, _, _, screen)| {
+                (name, captured::<CellList>(&screen).memory_usage(), captured::<RunList>(&screen).memory_usage())
+            }),
+            worm_buffer: WormBuffer::new(),
+        }
+    }
+}
+
+impl Default for WormBenchApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every cell written, in colored words
+fn text_screen() -> Buffer {
+    let colors = [CATPPUCCIN.text, CATPPUCCIN.blue, CATPPUCCIN.green, CATPPUCCIN.peach, CATPPUCCIN.mauve];
+    let words = Words::new(corpus::ENGLISH, Length::Chars(SCREEN.area() as usize))
+        .enumerate()
+        .map(|(i, word)| Span::styled(word, Style::default().fg(colors[i / 2 % colors.len()])));
+
+    let mut buf = Buffer::empty(SCREEN);
+    Paragraph::new(Line::from_iter(words))
+        .wrap(Wrap { trim: false })
+        .render(SCREEN, &mut buf);
+    buf
+}
+
+/// A header and a few lines of text, like the statistics screen
+fn sparse_screen() -> Buffer {
+    let mut buf = Buffer::empty(SCREEN);
+    render_header(Rect::new(0, 0, SCREEN.width, 3), &mut buf, "Mousefood Benchmark", CATPPUCCIN.mauve);
+    Paragraph::new(vec![
+        Line::styled("System Status", Style::default().fg(CATPPUCCIN.yellow)),
+        Line::from(""),
+        Line::styled("Terminal: 53x24", Style::default().fg(CATPPUCCIN.blue)),
+        Line::styled("Memory: 112KB used / 327KB total", Style::default().fg(CATPPUCCIN.blue)),
+        Line::styled("Free: 215KB", Style::default().fg(CATPPUCCIN.blue)),
+    ]).render(Rect::new(6, 4, 41, 5), &mut buf);
+    buf
+}
+
+/// A `WormBuffer` holding a capture of `screen`
+fn captured<S: CellStore>(screen: &Buffer) -> WormBuffer<S> {
+    let worm_buffer = WormBuffer::<S>::default();
+    let mut buf = Buffer::empty(screen.area);
+    worm_buffer.cached_render(screen.area, &mut buf, |buf| buf.content.clone_from_slice(&screen.content));
+    worm_buffer
+}
+
+/// Captures `screen` into a new `WormBuffer`; the widgets are stood in for by a copy of the screen
+fn capture<S: CellStore + 'static>(screen: Buffer) -> impl FnMut(u32) {
+    let mut buf = Buffer::empty(screen.area);
+
+    move |n| {
+        for _ in 0..n {
+            buf.reset();
+            let worm_buffer = WormBuffer::<S>::default();
+            worm_buffer.cached_render(screen.area, &mut buf, |buf| buf.content.clone_from_slice(&screen.content));
+            core::hint::black_box(&worm_buffer);
+        }
+    }
+}
+
+/// Replays a capture of `screen`
+fn replay<S: CellStore + 'static>(screen: Buffer) -> impl FnMut(u32) {
+    let worm_buffer = captured::<S>(&screen);
+    let mut buf = Buffer::empty(screen.area);
+
+    move |n| {
+        for _ in 0..n {
+            worm_buffer.cached_render(screen.area, &mut buf, |_| unreachable!("already captured"));
+            core::hint::black_box(&buf);
+        }
+    }
+}
+
+impl Scene for WormBenchApp {
+    fn update(&mut self, _elapsed: Duration) {
+        self.suite.run_next();
+
+        thread::sleep(Duration::from_millis(16)); // ~60 FPS
+    }
+
+    fn render(&mut self, frame: &mut Frame, fps: &FpsWidget) {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Percentage(100),
+            Constraint::Length(3),
+        ]).split(area);
+
+        // the screen only changes when a benchmark finishes
+        self.worm_buffer.cached_render_keyed(&self.suite.completed(), area, buf, |buf| {
+            self.render_header(layout[0], buf);
+            self.render_results(layout[1], buf);
+        });
+        self.render_footer(layout[2], buf, fps);
+    }
+
+    fn is_busy(&self) -> bool {
+        !self.suite.is_done()
+    }
+
+    fn suite(&self) -> Option<&Suite> {
+        Some(&self.suite)
+    }
+
+    fn summary(&self) -> Option<CompactString> {
+        Some(if self.suite.is_done() {
+            format_compact!("{} ms", self.suite.total().as_millis())
+        } else {
+            format_compact!("{}/{} done", self.suite.completed(), self.suite.len())
+        })
+    }
+}
+
+impl WormBenchApp {
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let title = format_compact!("WormBuffer Benchmark [{}/{}]", self.suite.completed(), self.suite.len());
+        render_header(area, buf, &title, CATPPUCCIN.blue);
+    }
+
+    fn render_results(&self, area: Rect, buf: &mut Buffer) {
+        let header = Row::new(
+            [Cell::from("ns/iter  ").style(Style::default().fg(CATPPUCCIN.text))].into_iter()
+                .chain(GROUPS.map(|(group, color)| Cell::from(group).style(Style::default().fg(color))))
+                .chain([Cell::from(" Ratio ").style(Style::default().fg(CATPPUCCIN.peach))])
+        );
+
+        let rows = self.suite.names_in(&GROUPS.map(|(group, _)| group))
+            .into_iter()
+            .map(|name| self.create_timing_row(name))
+            .chain([Row::new([Cell::from("")])])
+            .chain(self.memory.iter().map(|&(screen, cells, runs)| memory_row(screen, cells, runs)));
+
+        let table = Table::new(rows, [Constraint::Length(9), Constraint::Length(10), Constraint::Length(10), Constraint::Length(7)])
+            .header(header)
+            .block(Block::new());
+
+        let table_area = Rect {
+            x: area.x + 6,
+            y: area.y,
+            width: area.width - 6,
+            height: area.height,
+        };
+        table.render(table_area, buf);
+    }
+
+    fn create_timing_row(&self, name: &'static str) -> Row<'static> {
+        let [(cells_group, cells_color), (runs_group, runs_color)] = GROUPS;
+        let cells_state = self.suite.state(name, cells_group);
+        let runs_state = self.suite.state(name, runs_group);
+
+        // how many times faster the runs are
+        let ratio_cell = if let (Some(BenchState::Done(cells)), Some(BenchState::Done(runs))) = (cells_state, runs_state) {
+            ratio_cell(cells.ns_per_iter() / runs.ns_per_iter())
+        } else {
+            let color = if cells_state == Some(BenchState::Running) || runs_state == Some(BenchState::Running) {
+                CATPPUCCIN.yellow
+            } else {
+                CATPPUCCIN.surface2
+            };
+            Cell::from("---").style(Style::default().fg(color))
+        };
+
+        Row::new(vec![
+            Cell::from(format!("{name:<9}")).style(Style::default().fg(CATPPUCCIN.text)),
+            format_benchmark_cell(cells_state, cells_color),
+            format_benchmark_cell(runs_state, runs_color),
+            ratio_cell,
+        ])
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, fps: &FpsWidget) {
+        let fps_area = area.inner(Margin::new(8, 0));
+        fps.render(fps_area, buf);
+    }
+}
+
+/// Heap of a capture with either store, and how many times smaller the runs are
+fn memory_row(screen: &'static str, cells: MemoryUsage, runs: MemoryUsage) -> Row<'static> {
+    let [(_, cells_color), (_, runs_color)] = GROUPS;
+    let kb = |usage: MemoryUsage, color: Color| {
+        let text = format!("{:.1} KB", usage.heap_bytes as f64 / 1024.0);
+        Cell::from(Text::from(text).alignment(Alignment::Right)).style(Style::default().fg(color))
+    };
+
+    Row::new(vec![
+        Cell::from(format!("{screen:<9}")).style(Style::default().fg(CATPPUCCIN.text)),
+        kb(cells, cells_color),
+        kb(runs, runs_color),
+        ratio_cell(cells.heap_bytes as f64 / runs.heap_bytes.max(1) as f64),
+    ])
+}
+
+fn ratio_cell(ratio: f64) -> Cell<'static> {
+    let text = Text::from(format!("{:.1}x", ratio)).alignment(Alignment::Right);
+    let color = if ratio > 1.0 { CATPPUCCIN.teal } else { CATPPUCCIN.green };
+    Cell::from(text).style(Style::default().fg(color))
+}
+
+fn format_benchmark_cell(state: Option<BenchState>, completed_color: Color) -> Cell<'static> {
+    let (text, color) = match state {
+        Some(BenchState::Done(measurement)) => (measurement.to_string(), completed_color),
+        Some(BenchState::Running) => ("Running".to_string(), CATPPUCCIN.yellow),
+        Some(BenchState::Pending) => ("Pending".to_string(), CATPPUCCIN.surface2),
+        None => ("Error".to_string(), CATPPUCCIN.red),
+    };
+
+    Cell::from(Text::from(text).alignment(Alignment::Right)).style(Style::default().fg(color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stores_capture_the_same_screens() {
+        for screen in [text_screen(), sparse_screen()] {
+            let cells = captured::<CellList>(&screen);
+            let runs = captured::<RunList>(&screen);
+            assert_eq!(cells.memory_usage().cells, runs.memory_usage().cells);
+            assert!(runs.memory_usage().heap_bytes < cells.memory_usage().heap_bytes);
+
+            let mut replayed = Buffer::empty(SCREEN);
+            runs.cached_render(SCREEN, &mut replayed, |_| unreachable!());
+            assert_eq!(replayed, screen);
+        }
+    }
+}
@@ -3,6 +3,8 @@ use std::hash::{BuildHasher, Hash, Hasher};
 use foldhash::fast::RandomState;
 use ratatui::buffer::{Buffer, Cell};
 use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Modifier};
+use crate::cell_store::{CellList, CellStore};
 
 /// Caches and replays ratatui buffer changes for optimized rendering
 /// 
@@ -13,34 +15,185 @@ use ratatui::layout::{Position, Rect};
 /// the widget rendering logic.
 /// 
 /// Once populated, the buffer becomes read-only for rendering until [`reset()`](Self::reset)
-/// is called to clear the cache and allow new content to be captured.
+/// is called to clear the cache and allow new content to be captured, or
+/// [`invalidate()`](Self::invalidate) drops the cells of an area to capture it again.
 /// 
+/// [`cached_render_keyed()`](Self::cached_render_keyed) ties the capture to a key instead,
+/// capturing again whenever the key changes; with [`with_snapshots()`](Self::with_snapshots)
+/// the captures of recent keys are kept, so switching back to one of them just replays it.
+///
+/// By default only the cells whose content changed while rendering are recorded, so
+/// a cell the widgets write with what was already there isn't replayed; the
+/// [`CaptureMode::Written`] mode records every written cell instead.
+///
+/// The cells are kept in a [`CellStore`]: [`CellList`] by default, or the more
+/// compact [`RunList`](crate::cell_store::RunList) as `WormBuffer<RunList>`.
+///
 /// This is particularly useful for embedded systems where rendering performance
 /// is critical and complex layouts should be cached when possible.
 #[derive(Debug, Default, Clone)]
-pub struct WormBuffer {
-    cells: RefCell<Vec<(Position, Cell)>>,
+pub struct WormBuffer<S: CellStore = CellList> {
+    cells: RefCell<S>,
+    /// Areas to capture again on the next render
+    invalidated: RefCell<Vec<Rect>>,
+    /// Hash of the key `cells` were captured for, if captured by key
+    key: std::cell::Cell<Option<u64>>,
+    /// Captures of previous keys, most recently used first
+    snapshots: RefCell<Vec<(u64, S)>>,
+    /// How many captures of previous keys to keep
+    capacity: usize,
+    capture_mode: CaptureMode,
     hasher_state: RandomState,
 }
 
+/// Which cells [`WormBuffer::cached_render()`] records
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureMode {
+    /// Cells whose content differs after rendering, found by hashing them before
+    ///
+    /// Cheap, but a cell written with the content it already had is missed, and
+    /// replaying over a buffer holding something else there leaves that in place.
+    #[default]
+    Changed,
+    /// Every cell the widgets write, found by rendering them twice, over two sets
+    /// of sentinel cells that differ in every part
+    ///
+    /// Replays the same whatever the buffer held before. Where the widgets only set
+    /// some of a cell, e.g. its style, the parts they left alone are recorded as
+    /// they were when captured.
+    Written,
+}
+
+/// The two cells rendered over in [`CaptureMode::Written`]; a part of a cell is
+/// only left alone if it holds the first's after one render and the second's after
+/// the other
+fn sentinels() -> [Cell; 2] {
+    let mut first = Cell::new("\u{FFFF}");
+    first.set_fg(Color::Indexed(254)).set_bg(Color::Indexed(253));
+    first.modifier = Modifier::empty();
+
+    let mut second = Cell::new("\u{FFFE}");
+    second.set_fg(Color::Indexed(253)).set_bg(Color::Indexed(254));
+    second.modifier = Modifier::all();
+    [first, second]
+}
+
+/// Cells and heap bytes held by a [`WormBuffer`], snapshots included
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub cells: usize,
+    pub heap_bytes: usize,
+}
+
 impl WormBuffer {
-    /// Creates a new empty buffer
+    /// Creates a new empty buffer storing a [`CellList`]; other stores start from `default()`
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<S: CellStore> WormBuffer<S> {
+    /// Creates a buffer keeping the captures of up to `capacity` previous keys,
+    /// besides the current one
+    pub fn with_snapshots(capacity: usize) -> Self {
+        Self { capacity, ..Self::default() }
+    }
 
     /// Combines this buffer with another, consuming self
     /// 
     /// # Arguments
     /// * `other` - Buffer to merge cells from
     pub fn combine(self, other: &Self) -> Self {
-        self.cells.borrow_mut().extend_from_slice(&other.cells.borrow());
+        self.cells.borrow_mut().extend(&other.cells.borrow());
         self
     }
+
+    /// Sets which cells are recorded from now on
+    pub fn with_capture_mode(mut self, mode: CaptureMode) -> Self {
+        self.capture_mode = mode;
+        self
+    }
+
+    /// Encodes the current capture as a [`Snapshot`](crate::snapshot::Snapshot) of `area`
+    pub fn to_snapshot(&self, area: Rect) -> Vec<u8> {
+        crate::snapshot::encode(area, self.cells.borrow().cells())
+    }
+
+    /// Cells recorded for the current capture and the snapshots, and the heap they take
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let snapshots = self.snapshots.borrow();
+        let cells = self.cells.borrow();
+        let stores = std::iter::once(&*cells).chain(snapshots.iter().map(|(_, store)| store));
+
+        stores.fold(
+            MemoryUsage { cells: 0, heap_bytes: snapshots.capacity() * size_of::<(u64, S)>() },
+            |usage, store| MemoryUsage {
+                cells: usage.cells + store.len(),
+                heap_bytes: usage.heap_bytes + store.heap_bytes(),
+            },
+        )
+    }
     
-    /// Clears all cached cells
+    /// Clears all cached cells, including the snapshots of previous keys
     pub fn reset(&mut self) {
-        self.cells.borrow_mut().clear();
+        *self.cells.get_mut() = S::default();
+        self.invalidated.get_mut().clear();
+        self.key.set(None);
+        self.snapshots.get_mut().clear();
+    }
+
+    /// Drops the cached cells inside `area`, capturing just that area on the next render
+    ///
+    /// The rest of the cache stays valid, so a mostly static screen can keep
+    /// replaying while a few live fields update. Snapshots of previous keys are
+    /// dropped, as their content in `area` is just as stale.
+    pub fn invalidate(&mut self, area: Rect) {
+        self.cells.get_mut().remove_area(area);
+        self.invalidated.get_mut().push(area);
+        self.snapshots.get_mut().clear();
+    }
+
+    /// Like [`cached_render()`](Self::cached_render), capturing again when the hash of
+    /// `key` differs from the one the cache was captured with
+    ///
+    /// The key stands for whatever the rendered content depends on, e.g. a progress
+    /// counter or a results struct. The capture being replaced is kept as a snapshot
+    /// if the buffer has room for one, and a snapshot of `key` is restored instead of
+    /// rendering again.
+    pub fn cached_render_keyed(
+        &self,
+        key: &impl Hash,
+        area: Rect,
+        buf: &mut Buffer,
+        render_widgets: impl FnMut(&mut Buffer)
+    ) {
+        let key = self.hasher_state.hash_one(key);
+        if self.key.get() != Some(key) {
+            self.switch_key(key);
+        }
+
+        self.cached_render(area, buf, render_widgets);
+    }
+
+    /// Stashes the current capture and restores the snapshot of `key`, if there is one
+    fn switch_key(&self, key: u64) {
+        let mut snapshots = self.snapshots.borrow_mut();
+        let restored = snapshots.iter()
+            .position(|(k, _)| *k == key)
+            .map(|i| snapshots.remove(i).1)
+            .unwrap_or_default();
+        let cells = self.cells.replace(restored);
+
+        // a capture with areas still to re-capture is incomplete, so it isn't kept
+        let pending = !self.invalidated.take().is_empty();
+        if let Some(previous) = self.key.replace(Some(key))
+            && self.capacity > 0
+            && !cells.is_empty()
+            && !pending
+        {
+            snapshots.insert(0, (previous, cells));
+            snapshots.truncate(self.capacity);
+        }
     }
 
     /// Renders widgets, caching only changed cells for future replays
@@ -48,23 +201,49 @@ impl WormBuffer {
     /// # Arguments
     /// * `area` - Rectangular area to render within
     /// * `buf` - Target buffer to render into
-    /// * `render_widgets` - Closure that performs the actual widget rendering (only called when buffer is empty)
+    /// * `render_widgets` - Closure that performs the actual widget rendering (only called when
+    ///   the buffer is empty or has invalidated areas, twice per capture with
+    ///   [`CaptureMode::Written`])
     pub fn cached_render(
         &self,
         area: Rect,
         buf: &mut Buffer,
-        mut render_widgets: impl FnMut(&mut Buffer)
+        render_widgets: impl FnMut(&mut Buffer)
     ) {
+        let captured = !self.cells.borrow().is_empty();
+        let invalidated = self.invalidated.take();
+
         // replay the recorded cells if the buffer has already been captured
-        if !self.cells.borrow().is_empty() {
+        if captured && invalidated.is_empty() {
             self.render(area, buf);
             return;
         }
 
-        // make a record of the current state of the buffer by hashing each cell
+        // capture the whole area the first time, and only the invalidated parts after that
         let area = area.intersection(buf.area);
-        let cell_hashes: Vec<u64> = area.positions()
-            .map(|pos| self.cell_hash(&buf[pos]))
+        let regions = if captured {
+            invalidated.iter().map(|region| region.intersection(area)).collect()
+        } else {
+            vec![area]
+        };
+
+        // make a record of the current state of the buffer by hashing each cell,
+        // visiting cells of overlapping regions once
+        let positions: Vec<Position> = regions.iter().enumerate()
+            .flat_map(|(i, region)| region.positions().map(move |pos| (i, pos)))
+            .filter(|&(i, pos)| !regions[..i].iter().any(|r| r.contains(pos)))
+            .map(|(_, pos)| pos)
+            .collect();
+
+        match self.capture_mode {
+            CaptureMode::Changed => self.capture_changed(&positions, buf, render_widgets),
+            CaptureMode::Written => self.capture_written(&positions, buf, render_widgets),
+        }
+    }
+
+    fn capture_changed(&self, positions: &[Position], buf: &mut Buffer, render_widgets: impl FnOnce(&mut Buffer)) {
+        let cell_hashes: Vec<u64> = positions.iter()
+            .map(|&pos| self.cell_hash(&buf[pos]))
             .collect();
 
         // render the widgets into the buffer
@@ -73,18 +252,54 @@ impl WormBuffer {
         // compare the new state of the buffer to the old state,
         // and record any changed cells
         let mut cells = self.cells.borrow_mut();
-        area.positions()
+        positions.iter()
             .zip(cell_hashes)
-            .filter(|&(pos, old_hash)| old_hash != self.cell_hash(&buf[pos]))
-            .for_each(|(pos, _)| cells.push((pos, buf[pos].clone())));
+            .filter(|&(&pos, old_hash)| old_hash != self.cell_hash(&buf[pos]))
+            .for_each(|(&pos, _)| cells.push(pos, &buf[pos]));
+    }
+
+    fn capture_written(&self, positions: &[Position], buf: &mut Buffer, mut render_widgets: impl FnMut(&mut Buffer)) {
+        let [first, second] = sentinels();
+        let mut render_over = |sentinel: &Cell, buf: &mut Buffer| -> Vec<Cell> {
+            let replaced = positions.iter()
+                .map(|&pos| std::mem::replace(&mut buf[pos], sentinel.clone()))
+                .collect();
+            render_widgets(buf);
+            replaced
+        };
+
+        // render over each sentinel, keeping what they replace
+        let originals = render_over(&first, buf);
+        let over_first = render_over(&second, buf);
+
+        let mut cells = self.cells.borrow_mut();
+        for ((&pos, original), mut cell) in positions.iter().zip(originals).zip(over_first) {
+            let over_second = &buf[pos];
+            if cell == first && *over_second == second {
+                buf[pos] = original;
+                continue;
+            }
+
+            // parts the widgets left alone keep what was there before
+            if cell.symbol() == first.symbol() && over_second.symbol() == second.symbol() {
+                cell.set_symbol(original.symbol());
+            }
+            if cell.fg == first.fg && over_second.fg == second.fg {
+                cell.fg = original.fg;
+            }
+            if cell.bg == first.bg && over_second.bg == second.bg {
+                cell.bg = original.bg;
+            }
+            // the modifiers added over none, less those removed from all
+            cell.modifier = (original.modifier | cell.modifier) & over_second.modifier;
+
+            cells.push(pos, &cell);
+            buf[pos] = cell;
+        }
     }
 
     fn render(&self, _area: Rect, buf: &mut Buffer) {
-        self.cells
-            .borrow()
-            .iter()
-            .cloned()
-            .for_each(|(pos, cell)| buf[pos] = cell);
+        self.cells.borrow().replay(buf);
     }
 
     fn cell_hash(&self, cell: &Cell) -> u64 {
@@ -94,16 +309,15 @@ impl WormBuffer {
 
 impl Into<WormBuffer> for &Buffer {
     fn into(self) -> WormBuffer {
-        let cells = self
-            .area
+        let mut cells = CellList::default();
+        self.area
             .positions()
-            .map(|pos| (pos, self[pos].clone()))
-            .filter(|(_, cell)| *cell != Cell::EMPTY)
-            .collect();
+            .filter(|&pos| self[pos] != Cell::EMPTY)
+            .for_each(|pos| cells.push(pos, &self[pos]));
 
         WormBuffer {
             cells: RefCell::new(cells),
-            hasher_state: RandomState::default(),
+            ..WormBuffer::default()
         }
     }
 }
@@ -111,8 +325,8 @@ impl Into<WormBuffer> for &Buffer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ratatui::style::{Color, Style};
-    use ratatui::widgets::{Block, Borders, Paragraph, Widget};
+    use ratatui::style::{Style, Stylize};
+    use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
 
     #[test]
     fn test_worm_buffer_equivalent_to_direct_render() {
@@ -141,6 +355,136 @@ mod tests {
         assert_buffer_eq(&direct_buf, &cached_buf, area);
     }
 
+    #[test]
+    fn test_invalidate_recaptures_only_the_area() {
+        let area = Rect::new(0, 0, 10, 3);
+        let render = |buf: &mut Buffer, value: &str| {
+            Paragraph::new(format!("static\nlive {value}")).render(area, buf);
+        };
+
+        let mut worm_buf = WormBuffer::new();
+        let mut renders = 0;
+        for value in ["1", "2"] {
+            let mut buf = Buffer::empty(area);
+            worm_buf.cached_render(area, &mut buf, |buf| {
+                renders += 1;
+                render(buf, value);
+            });
+        }
+        assert_eq!(renders, 1);
+
+        // the static row is replayed from the cache, the live row captured anew
+        worm_buf.invalidate(Rect::new(5, 1, 5, 1));
+        for value in ["3", "4"] {
+            let mut buf = Buffer::empty(area);
+            worm_buf.cached_render(area, &mut buf, |buf| render(buf, value));
+            assert_eq!(buf, Buffer::with_lines(["static    ", "live 3    ", "          "]));
+        }
+
+        // cells outside the invalidated area are never captured again
+        worm_buf.invalidate(Rect::new(0, 0, 10, 1));
+        let mut buf = Buffer::empty(area);
+        worm_buf.cached_render(area, &mut buf, |buf| Paragraph::new("fresh\nlive 5").render(area, buf));
+        let mut replayed = Buffer::empty(area);
+        worm_buf.cached_render(area, &mut replayed, |_| unreachable!());
+        assert_eq!(replayed, Buffer::with_lines(["fresh     ", "live 3    ", "          "]));
+    }
+
+    #[test]
+    fn test_keyed_render_recaptures_on_new_key() {
+        let area = Rect::new(0, 0, 8, 1);
+        let worm_buf = WormBuffer::new();
+        let mut renders = 0;
+
+        for (key, expected) in [(1, "key 1"), (1, "key 1"), (2, "key 2"), (1, "key 1")] {
+            let mut buf = Buffer::empty(area);
+            worm_buf.cached_render_keyed(&key, area, &mut buf, |buf| {
+                renders += 1;
+                Paragraph::new(format!("key {key}")).render(area, buf);
+            });
+            assert_eq!(buf, Buffer::with_lines([format!("{expected:8}")]));
+        }
+
+        assert_eq!(renders, 3);
+    }
+
+    #[test]
+    fn test_snapshots_replay_recent_keys() {
+        let area = Rect::new(0, 0, 8, 1);
+        let worm_buf: WormBuffer = WormBuffer::with_snapshots(1);
+        let mut renders = Vec::new();
+
+        for key in ["a", "b", "a", "b", "c", "a", "b"] {
+            let mut buf = Buffer::empty(area);
+            worm_buf.cached_render_keyed(&key, area, &mut buf, |buf| {
+                renders.push(key);
+                Paragraph::new(key).render(area, buf);
+            });
+            assert_eq!(buf, Buffer::with_lines([format!("{key:8}")]));
+        }
+
+        // only the most recent previous key is kept
+        assert_eq!(renders, ["a", "b", "c", "a", "b"]);
+    }
+
+    /// Blanks over a cleared buffer: the widget writes what is already there
+    fn render_blanks(buf: &mut Buffer) {
+        Clear.render(Rect::new(0, 0, 4, 2), buf);
+        Paragraph::new("ab").render(Rect::new(0, 1, 4, 1), buf);
+    }
+
+    #[test]
+    fn test_changed_capture_misses_unchanged_writes() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut expected = Buffer::filled(area, Cell::new("x"));
+        render_blanks(&mut expected);
+
+        for (mode, cells) in [(CaptureMode::Changed, 2), (CaptureMode::Written, 8)] {
+            let worm_buf = WormBuffer::new().with_capture_mode(mode);
+            worm_buf.cached_render(area, &mut Buffer::empty(area), render_blanks);
+            assert_eq!(worm_buf.memory_usage().cells, cells, "{mode:?}");
+
+            // replayed over other content, only the written cells replace it
+            let mut replayed = Buffer::filled(area, Cell::new("x"));
+            worm_buf.cached_render(area, &mut replayed, |_| unreachable!());
+            if mode == CaptureMode::Changed {
+                assert_eq!(replayed, Buffer::with_lines(["xxxx", "abxx"]));
+            } else {
+                assert_eq!(replayed, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_written_capture_keeps_untouched_cells() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buf = Buffer::with_lines(["1234", "5678"]);
+        buf[(0, 1)].modifier = Modifier::BOLD | Modifier::ITALIC;
+        let write = |buf: &mut Buffer| {
+            buf[(1, 0)].set_symbol("a");
+            buf[(2, 1)].set_bg(Color::Blue).set_style(Modifier::UNDERLINED);
+            buf[(0, 1)].set_style(Style::new().not_italic());
+            // the sentinels' own colors are written like any other
+            buf[(3, 0)].set_fg(Color::Indexed(254)).set_bg(Color::Indexed(253));
+        };
+
+        let mut expected = buf.clone();
+        write(&mut expected);
+        let worm_buf = WormBuffer::new().with_capture_mode(CaptureMode::Written);
+        worm_buf.cached_render(area, &mut buf, write);
+        assert_eq!(buf, expected);
+        assert_eq!(worm_buf.memory_usage().cells, 4);
+
+        // the restyled cells keep the symbols and modifiers they had when captured
+        let mut replayed = Buffer::empty(area);
+        worm_buf.cached_render(area, &mut replayed, |_| unreachable!());
+        let mut expected = Buffer::with_lines([" a 4", "5 7 "]);
+        expected[(2, 1)].set_bg(Color::Blue).set_style(Modifier::UNDERLINED);
+        expected[(0, 1)].set_style(Modifier::BOLD);
+        expected[(3, 0)].set_fg(Color::Indexed(254)).set_bg(Color::Indexed(253));
+        assert_eq!(replayed, expected);
+    }
+
     fn assert_buffer_eq(expected: &Buffer, actual: &Buffer, area: Rect) {
         for pos in area.positions() {
             let expected_cell = &expected[pos];